@@ -0,0 +1,13 @@
+use crate::models::ReactionEmoji;
+
+/// Discord requests queued by input handlers and executed by the main loop
+#[derive(Debug, Clone)]
+pub enum PendingAction {
+    DeleteMessage { channel_id: String, message_id: String },
+    AddReaction { channel_id: String, message_id: String, emoji: ReactionEmoji },
+    RemoveReaction { channel_id: String, message_id: String, emoji: ReactionEmoji },
+    PinMessage { channel_id: String, message_id: String },
+    UnpinMessage { channel_id: String, message_id: String },
+    CreateForumPost { forum_id: String, title: String, content: String, tag_ids: Vec<String> },
+    TriggerTyping { channel_id: String },
+}
@@ -0,0 +1,51 @@
+/// Field focused in the new-post form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DraftField {
+    Title,
+    Tags,
+    Content,
+}
+
+/// A forum post being composed
+#[derive(Debug, Clone)]
+pub struct ForumDraft {
+    pub forum_id: String,
+    pub title: String,
+    pub content: String,
+    pub tag_ids: Vec<String>,
+    pub tag_cursor: usize,
+    pub field: DraftField,
+}
+
+impl ForumDraft {
+    pub fn new(forum_id: impl Into<String>) -> Self {
+        Self {
+            forum_id: forum_id.into(),
+            title: String::new(),
+            content: String::new(),
+            tag_ids: Vec::new(),
+            tag_cursor: 0,
+            field: DraftField::Title,
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.field = match self.field {
+            DraftField::Title => DraftField::Tags,
+            DraftField::Tags => DraftField::Content,
+            DraftField::Content => DraftField::Title,
+        };
+    }
+
+    pub fn toggle_tag(&mut self, tag_id: &str) {
+        if let Some(pos) = self.tag_ids.iter().position(|t| t == tag_id) {
+            self.tag_ids.remove(pos);
+        } else {
+            self.tag_ids.push(tag_id.to_string());
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        !self.title.trim().is_empty() && !self.content.trim().is_empty()
+    }
+}
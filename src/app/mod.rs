@@ -1,14 +1,29 @@
+mod action;
+mod forum;
+mod search;
+mod token;
+mod typing;
+mod unread;
 mod mode;
 mod sidebar;
 mod state;
 
+pub use action::PendingAction;
+pub use forum::{DraftField, ForumDraft};
+pub use search::SearchState;
+pub use token::TokenSetup;
 pub use mode::AppMode;
 pub use sidebar::SidebarItem;
+pub use typing::TypingUser;
+pub use unread::{ReadState, UnreadCount};
 
 use crate::config::{Config, Theme, load_theme, CacheAutoClear};
-use crate::models::{Guild, Message, AttachedFile, DmChannel, Notification, ChannelList};
-use crate::discord::DiscordClient;
+use crate::models::{Guild, Message, MessageAttachment, OutgoingMessage, SearchQuery, SearchScope, SendState, AttachedFile, DmChannel, Notification, Channel, ChannelList, ChannelType, ForumPost, ReferencedMessage, ReplyTarget};
+use crate::discord::{ChatBackend, ConnectionState};
+use crate::archive::{ArchivedSession, MessageArchive};
+use crate::notify::DesktopNotifier;
 use crate::ui::image::{ImageRenderer, CacheStats};
+use anyhow::Result;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::time::Instant;
@@ -26,6 +41,28 @@ pub struct App {
     pub selected_channel: Option<String>,
     pub messages: Vec<Message>,
     pub message_scroll: usize,
+    pub editing_message: Option<String>,
+    pub replying_to: Option<ReplyTarget>,
+    pub pending_delete: Option<String>,
+    pub pending_actions: Vec<PendingAction>,
+    pub outbox: Vec<OutgoingMessage>,
+    pub reaction_input: String,
+    pub pins: HashMap<String, Vec<Message>>,
+    pub pins_selected_idx: usize,
+    pub loading_pins: bool,
+    /// Whether we may pin in a channel, filled in the first time we pin or unpin there
+    pub pin_permissions: HashMap<String, bool>,
+    /// Message to scroll to once older history containing it has loaded
+    pub jump_target: Option<String>,
+    pub search: Option<SearchState>,
+    pub selected_forum: Option<String>,
+    pub forum_posts: HashMap<String, Vec<ForumPost>>,
+    pub forum_selected_idx: usize,
+    pub forum_draft: Option<ForumDraft>,
+    pub loading_forum_posts: bool,
+    pub current_user_id: Option<String>,
+    /// Our name as the gateway reported it on connecting
+    pub current_user_name: Option<String>,
     pub input: String,
     pub input_cursor: usize,
     pub attached_files: Vec<AttachedFile>,
@@ -33,13 +70,43 @@ pub struct App {
     pub expanded_categories: HashMap<String, HashSet<String>>,
     pub message_cache: HashMap<String, Vec<Message>>,
     pub config: Config,
-    pub typing_users: Vec<String>,
+    pub user_names: HashMap<String, String>,
+    pub role_names: HashMap<String, String>,
+    /// Roles we hold, keyed by guild ID; fetched once a message in the guild mentions a role
+    pub member_roles: HashMap<String, Vec<String>>,
+    pub channel_names: HashMap<String, String>,
+    pub failed_lookups: HashSet<String>,
+    pub typing_users: Vec<TypingUser>,
+    pub last_typing_sent: Option<Instant>,
+    pub read_state: ReadState,
     pub settings_selected: usize,
-    pub discord_client: Option<Arc<Mutex<DiscordClient>>>,
+    pub discord_client: Option<Arc<Mutex<Box<dyn ChatBackend>>>>,
+    /// Keyring name of the account we are signed in as, `None` in the demo
+    pub account: Option<String>,
+    /// Bumped on every account switch, so events still queued for the old account are dropped
+    pub account_generation: u64,
+    /// Account picked in the switcher, connected to by the main loop
+    pub switching_account: Option<String>,
+    pub accounts_selected_idx: usize,
+    pub token_setup: Option<TokenSetup>,
+    /// Account whose token the removal key was pressed on once
+    pub confirm_token_removal: Option<String>,
+    /// Account whose token the main loop should delete from the keyring
+    pub removing_token: Option<String>,
     pub loading_channels: bool,
     pub loading_messages: bool,
+    pub loading_older_messages: bool,
+    pub history_exhausted: HashSet<String>,
     pub loading_dms: bool,
+    pub connection: ConnectionState,
+    pub backfill_pending: bool,
+    pub archive: Option<MessageArchive>,
+    /// Discord was unreachable at startup, so we are browsing the archive read-only
+    pub offline: bool,
+    /// Channel whose archived messages are shown and still need checking against Discord
+    pub reconcile_pending: Option<String>,
     pub notifications: Vec<Notification>,
+    pub desktop_notifier: Option<DesktopNotifier>,
     pub image_renderer: ImageRenderer,
     pub cache_stats: Option<CacheStats>,
     pub last_cache_check: Instant,
@@ -49,6 +116,11 @@ pub struct App {
 
 impl App {
     pub fn new(config: Config) -> Self {
+        let image_renderer = ImageRenderer::new(&config.network);
+        Self::with_image_renderer(config, image_renderer)
+    }
+
+    fn with_image_renderer(config: Config, image_renderer: ImageRenderer) -> Self {
         Self {
             mode: AppMode::Sidebar,
             dms: Vec::new(),
@@ -60,6 +132,25 @@ impl App {
             selected_channel: None,
             messages: Vec::new(),
             message_scroll: 0,
+            editing_message: None,
+            replying_to: None,
+            pending_delete: None,
+            pending_actions: Vec::new(),
+            outbox: Vec::new(),
+            reaction_input: String::new(),
+            pins: HashMap::new(),
+            pins_selected_idx: 0,
+            loading_pins: false,
+            pin_permissions: HashMap::new(),
+            jump_target: None,
+            search: None,
+            selected_forum: None,
+            forum_posts: HashMap::new(),
+            forum_selected_idx: 0,
+            forum_draft: None,
+            loading_forum_posts: false,
+            current_user_id: None,
+            current_user_name: None,
             input: String::new(),
             input_cursor: 0,
             attached_files: Vec::new(),
@@ -67,14 +158,36 @@ impl App {
             expanded_categories: HashMap::new(),
             message_cache: HashMap::new(),
             config,
+            user_names: HashMap::new(),
+            role_names: HashMap::new(),
+            member_roles: HashMap::new(),
+            channel_names: HashMap::new(),
+            failed_lookups: HashSet::new(),
             typing_users: Vec::new(),
+            last_typing_sent: None,
+            read_state: ReadState::default(),
             settings_selected: 0,
             discord_client: None,
+            account: None,
+            account_generation: 0,
+            switching_account: None,
+            accounts_selected_idx: 0,
+            token_setup: None,
+            confirm_token_removal: None,
+            removing_token: None,
             loading_channels: false,
             loading_messages: false,
+            loading_older_messages: false,
+            history_exhausted: HashSet::new(),
             loading_dms: false,
+            connection: ConnectionState::Connecting,
+            backfill_pending: false,
+            archive: None,
+            offline: false,
+            reconcile_pending: None,
             notifications: Vec::new(),
-            image_renderer: ImageRenderer::new(),
+            desktop_notifier: None,
+            image_renderer,
             cache_stats: None,
             last_cache_check: Instant::now(),
             last_cache_clear: Instant::now(),
@@ -134,7 +247,577 @@ impl App {
         state::get_channel_name(&self.selected_channel, &self.dms, &self.channel_cache)
     }
 
-    pub fn set_discord_client(&mut self, client: DiscordClient) {
+    /// Guild that a channel or thread belongs to, if it is loaded
+    pub fn guild_id_for_channel(&self, channel_id: &str) -> Option<String> {
+        state::get_guild_id(channel_id, &self.channel_cache)
+    }
+
+    /// Look up a loaded guild channel or thread
+    pub fn find_channel(&self, channel_id: &str) -> Option<&Channel> {
+        self.channel_cache.values().find_map(|list| list.find(channel_id))
+    }
+
+    pub fn current_forum(&self) -> Option<&Channel> {
+        self.selected_forum
+            .as_deref()
+            .and_then(|id| self.find_channel(id))
+            .filter(|c| c.kind == ChannelType::Forum)
+    }
+
+    pub fn current_forum_posts(&self) -> &[ForumPost] {
+        self.selected_forum
+            .as_ref()
+            .and_then(|id| self.forum_posts.get(id))
+            .map(|posts| posts.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The message under the cursor in the message view
+    pub fn selected_message(&self) -> Option<&Message> {
+        self.messages.get(self.message_scroll)
+    }
+
+    pub fn is_own_message(&self, message: &Message) -> bool {
+        self.current_user_id.as_deref() == Some(message.author_id.as_str())
+    }
+
+    /// Apply `update` to a message in both the open channel and the cache
+    pub fn update_message(&mut self, channel_id: &str, message_id: &str, update: impl Fn(&mut Message)) {
+        if self.selected_channel.as_deref() == Some(channel_id) {
+            if let Some(msg) = self.messages.iter_mut().find(|m| m.id == message_id) {
+                update(msg);
+            }
+        }
+        if let Some(messages) = self.message_cache.get_mut(channel_id) {
+            if let Some(msg) = messages.iter_mut().find(|m| m.id == message_id) {
+                update(msg);
+            }
+        }
+    }
+
+    pub fn apply_message_edit(&mut self, channel_id: &str, message_id: &str, content: &str) {
+        self.update_message(channel_id, message_id, |msg| {
+            msg.content = content.to_string();
+            msg.edited = true;
+        });
+    }
+
+    pub fn remove_message(&mut self, channel_id: &str, message_id: &str) {
+        if self.selected_channel.as_deref() == Some(channel_id) {
+            self.messages.retain(|m| m.id != message_id);
+            self.message_scroll = self.message_scroll.min(self.messages.len().saturating_sub(1));
+        }
+        if let Some(messages) = self.message_cache.get_mut(channel_id) {
+            messages.retain(|m| m.id != message_id);
+        }
+        if self.editing_message.as_deref() == Some(message_id) {
+            self.editing_message = None;
+        }
+    }
+
+    pub fn current_pins(&self) -> &[Message] {
+        self.selected_channel
+            .as_ref()
+            .and_then(|id| self.pins.get(id))
+            .map(|pins| pins.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Store a channel's pins and bring the `pinned` flag of loaded messages in line
+    pub fn set_pins(&mut self, channel_id: &str, pins: Vec<Message>) {
+        let pinned: HashSet<String> = pins.iter().map(|m| m.id.clone()).collect();
+        if self.selected_channel.as_deref() == Some(channel_id) {
+            for msg in &mut self.messages {
+                msg.pinned = pinned.contains(&msg.id);
+            }
+            self.pins_selected_idx = self.pins_selected_idx.min(pins.len().saturating_sub(1));
+        }
+        if let Some(messages) = self.message_cache.get_mut(channel_id) {
+            for msg in messages {
+                msg.pinned = pinned.contains(&msg.id);
+            }
+        }
+        self.pins.insert(channel_id.to_string(), pins);
+    }
+
+    /// Apply a pin or unpin we made, ahead of the gateway telling us about it
+    pub fn set_pinned(&mut self, channel_id: &str, message_id: &str, pinned: bool) {
+        self.update_message(channel_id, message_id, |msg| msg.pinned = pinned);
+
+        let message = self.messages
+            .iter()
+            .chain(self.pins.get(channel_id).into_iter().flatten())
+            .find(|m| m.id == message_id)
+            .cloned();
+        if let Some(pins) = self.pins.get_mut(channel_id) {
+            pins.retain(|m| m.id != message_id);
+            if let Some(message) = message.filter(|_| pinned) {
+                pins.insert(0, message.with_pinned(true));
+            }
+        }
+        self.pins_selected_idx = self.pins_selected_idx.min(self.current_pins().len().saturating_sub(1));
+    }
+
+    /// Pinning is allowed unless we know otherwise; Discord has the final say
+    pub fn can_pin(&self, channel_id: &str) -> bool {
+        self.pin_permissions.get(channel_id).copied().unwrap_or(true)
+    }
+
+    /// What a search from the open channel covers: its whole guild, or just the DM
+    pub fn search_scope(&self) -> Option<SearchScope> {
+        let channel_id = self.selected_channel.clone()?;
+        Some(match self.guild_id_for_channel(&channel_id) {
+            Some(guild_id) => SearchScope::Guild(guild_id),
+            None => SearchScope::Channel(channel_id),
+        })
+    }
+
+    /// Parse the search input, looking up `from:` by user name and `in:` by channel name
+    pub fn build_search_query(&self) -> Result<SearchQuery> {
+        let Some(search) = &self.search else {
+            anyhow::bail!("No search is open");
+        };
+        let guild_id = match &search.scope {
+            SearchScope::Guild(id) => Some(id.as_str()),
+            SearchScope::Channel(_) => None,
+        };
+
+        let resolve_user = |value: &str| {
+            let value = value.trim_start_matches("<@").trim_start_matches('!').trim_start_matches('@').trim_end_matches('>');
+            if value.chars().all(|c| c.is_ascii_digit()) {
+                return Some(value.to_string());
+            }
+            self.user_names
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(value))
+                .map(|(id, _)| id.clone())
+        };
+        let resolve_channel = |value: &str| {
+            let value = value.trim_start_matches("<#").trim_start_matches('#').trim_end_matches('>');
+            if value.chars().all(|c| c.is_ascii_digit()) {
+                return Some(value.to_string());
+            }
+            guild_id
+                .and_then(|id| self.channel_cache.get(id))
+                .and_then(|list| {
+                    list.channels
+                        .iter()
+                        .chain(list.threads.iter())
+                        .find(|c| c.name.eq_ignore_ascii_case(value))
+                })
+                .map(|c| c.id.clone())
+        };
+
+        search::parse_query(&search.input, resolve_user, resolve_channel)
+    }
+
+    /// Scroll the open channel to a message if it is loaded
+    pub fn scroll_to_message(&mut self, message_id: &str) -> bool {
+        match self.messages.iter().position(|m| m.id == message_id) {
+            Some(idx) => {
+                self.message_scroll = idx;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Record the author and mentioned user names carried by messages
+    pub fn remember_names(&mut self, messages: &[Message]) {
+        for msg in messages {
+            self.user_names.insert(msg.author_id.clone(), msg.author.clone());
+            for user in &msg.mentioned_users {
+                self.user_names.insert(user.id.clone(), user.name.clone());
+            }
+        }
+    }
+
+    pub fn user_name(&self, user_id: &str) -> Option<&str> {
+        self.user_names.get(user_id).map(String::as_str)
+    }
+
+    pub fn role_name(&self, role_id: &str) -> Option<&str> {
+        self.role_names.get(role_id).map(String::as_str)
+    }
+
+    pub fn channel_name(&self, channel_id: &str) -> Option<&str> {
+        self.find_channel(channel_id)
+            .map(|c| c.name.as_str())
+            .or_else(|| self.channel_names.get(channel_id).map(String::as_str))
+    }
+
+    pub fn start_typing(&mut self, channel_id: &str, user_id: &str, name: String) {
+        if self.current_user_id.as_deref() == Some(user_id) {
+            return;
+        }
+        self.stop_typing(channel_id, user_id);
+        self.typing_users.push(TypingUser::new(channel_id, user_id, name));
+    }
+
+    pub fn stop_typing(&mut self, channel_id: &str, user_id: &str) {
+        self.typing_users.retain(|t| t.channel_id != channel_id || t.user_id != user_id);
+    }
+
+    pub fn clear_expired_typing(&mut self) {
+        self.typing_users.retain(|t| !t.is_expired());
+    }
+
+    /// Names of the users typing in the open channel
+    pub fn current_typing_names(&self) -> Vec<&str> {
+        self.typing_users
+            .iter()
+            .filter(|t| self.selected_channel.as_deref() == Some(t.channel_id.as_str()))
+            .map(|t| t.name.as_str())
+            .collect()
+    }
+
+    /// Whether enough time has passed to send another typing trigger
+    pub fn should_send_typing(&self) -> bool {
+        self.last_typing_sent
+            .is_none_or(|sent| sent.elapsed() >= typing::TYPING_TRIGGER_INTERVAL)
+    }
+
+    /// Prepend an older page of history, keeping the same message at the top of the view
+    pub fn prepend_messages(&mut self, channel_id: &str, older: Vec<Message>) {
+        if self.selected_channel.as_deref() == Some(channel_id) {
+            self.message_scroll += older.len();
+            self.messages.splice(0..0, older.iter().cloned());
+        }
+        if let Some(messages) = self.message_cache.get_mut(channel_id) {
+            messages.splice(0..0, older);
+        }
+    }
+
+    /// Put a message in the outbox and show it straight away as a placeholder
+    pub fn queue_message(&mut self, outgoing: OutgoingMessage) {
+        let attachments = outgoing.file_paths
+            .iter()
+            .enumerate()
+            .map(|(i, path)| {
+                let name = std::path::Path::new(path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(path);
+                MessageAttachment::new(format!("{}_{}", outgoing.nonce, i), name, "", "", None, None, None)
+            })
+            .collect();
+
+        let placeholder = Message::new(
+            outgoing.nonce.clone(),
+            outgoing.channel_id.clone(),
+            self.current_user_name.clone().unwrap_or_else(|| self.config.general.username.clone()),
+            self.current_user_id.clone().unwrap_or_default(),
+            None,
+            outgoing.content.clone(),
+            chrono::Utc::now().format("%H:%M:%S").to_string(),
+        )
+        .with_attachments(attachments)
+        .with_reply_to(outgoing.reply_to.as_ref().map(|r| {
+            let original = self.messages.iter().find(|m| m.id == r.message_id);
+            ReferencedMessage::new(
+                r.message_id.clone(),
+                r.author.clone(),
+                original.map(|m| m.author_id.clone()).unwrap_or_default(),
+                original.map(|m| m.content.clone()).unwrap_or_default(),
+            )
+        }))
+        .with_nonce(Some(outgoing.nonce.clone()))
+        .with_send_state(Some(SendState::Queued));
+
+        if self.selected_channel.as_deref() == Some(outgoing.channel_id.as_str()) {
+            self.messages.push(placeholder.clone());
+        }
+        if let Some(messages) = self.message_cache.get_mut(&outgoing.channel_id) {
+            messages.push(placeholder);
+        }
+        self.outbox.push(outgoing);
+    }
+
+    pub fn set_send_state(&mut self, channel_id: &str, nonce: &str, state: SendState) {
+        if let Some(outgoing) = self.outbox.iter_mut().find(|o| o.nonce == nonce) {
+            outgoing.state = state.clone();
+        }
+        self.update_message(channel_id, nonce, |msg| msg.send_state = Some(state.clone()));
+    }
+
+    /// Swap the placeholder for the message Discord stored. Returns false when
+    /// the nonce is not one of ours.
+    pub fn reconcile_sent(&mut self, nonce: &str, sent: Message) -> bool {
+        let Some(index) = self.outbox.iter().position(|o| o.nonce == nonce) else {
+            return false;
+        };
+        self.outbox.remove(index);
+
+        let replace = |messages: &mut Vec<Message>| {
+            let already_there = messages.iter().any(|m| m.id == sent.id);
+            if let Some(pos) = messages.iter().position(|m| m.id == nonce) {
+                if already_there {
+                    messages.remove(pos);
+                } else {
+                    messages[pos] = sent.clone();
+                }
+            }
+        };
+
+        if self.selected_channel.as_deref() == Some(sent.channel_id.as_str()) {
+            replace(&mut self.messages);
+            self.message_scroll = self.message_scroll.min(self.messages.len().saturating_sub(1));
+        }
+        if let Some(messages) = self.message_cache.get_mut(&sent.channel_id) {
+            replace(messages);
+        }
+        true
+    }
+
+    /// Queue a failed message to be sent again
+    pub fn retry_message(&mut self, channel_id: &str, nonce: &str) {
+        self.set_send_state(channel_id, nonce, SendState::Queued);
+    }
+
+    /// Drop a queued or failed message and its placeholder
+    pub fn discard_message(&mut self, channel_id: &str, nonce: &str) {
+        self.outbox.retain(|o| o.nonce != nonce);
+        self.remove_message(channel_id, nonce);
+    }
+
+    /// Append messages fetched after a reconnect, skipping any that already arrived live
+    pub fn append_missed_messages(&mut self, channel_id: &str, missed: Vec<Message>) -> Vec<Message> {
+        let Some(cached) = self.message_cache.get_mut(channel_id) else {
+            return Vec::new();
+        };
+
+        let missed: Vec<Message> = missed
+            .into_iter()
+            .filter(|m| !cached.iter().any(|c| c.id == m.id))
+            .collect();
+        // They were sent before anything still in our outbox
+        let at = after_last_sent(cached);
+        cached.splice(at..at, missed.iter().cloned());
+
+        if self.selected_channel.as_deref() == Some(channel_id) {
+            let at = after_last_sent(&self.messages);
+            self.messages.splice(at..at, missed.iter().cloned());
+        }
+        missed
+    }
+
+    /// Whether the oldest message in the channel has been loaded
+    pub fn is_history_exhausted(&self, channel_id: &str) -> bool {
+        self.history_exhausted.contains(channel_id)
+    }
+
+    /// Gateway messages only carry a guild ID when they were sent in a guild
+    pub fn is_direct_message(&self, message: &Message) -> bool {
+        self.dms.iter().any(|dm| dm.id == message.channel_id)
+            || (message.guild_id.is_none() && self.guild_id_for_channel(&message.channel_id).is_none())
+    }
+
+    /// Whether a message should ping us: DMs, direct mentions, @everyone/@here and
+    /// mentions of a role we hold
+    pub fn mentions_me(&self, message: &Message) -> bool {
+        if self.is_own_message(message) {
+            return false;
+        }
+        if message.mentions_everyone || self.is_direct_message(message) {
+            return true;
+        }
+        if self.mentions_my_role(message) {
+            return true;
+        }
+        self.current_user_id
+            .as_deref()
+            .is_some_and(|me| message.mentioned_users.iter().any(|u| u.id == me))
+    }
+
+    fn mentions_my_role(&self, message: &Message) -> bool {
+        if message.mention_roles.is_empty() {
+            return false;
+        }
+        message.guild_id.clone()
+            .or_else(|| self.guild_id_for_channel(&message.channel_id))
+            .and_then(|guild_id| self.member_roles.get(&guild_id))
+            .is_some_and(|roles| message.mention_roles.iter().any(|role| roles.contains(role)))
+    }
+
+    /// Title for a desktop notification about `message`, or `None` when it should stay quiet
+    pub fn desktop_notification_title(&self, message: &Message) -> Option<String> {
+        let settings = &self.config.notifications;
+        if !settings.enabled
+            || settings.do_not_disturb
+            || self.is_own_message(message)
+            || self.selected_channel.as_deref() == Some(message.channel_id.as_str())
+            || self.is_channel_muted(&message.channel_id)
+        {
+            return None;
+        }
+
+        if self.is_direct_message(message) {
+            return settings.direct_messages.then(|| message.author.clone());
+        }
+
+        let wanted = (settings.mentions && self.mentions_me(message))
+            || settings.matches_keyword(&message.content);
+        if !wanted {
+            return None;
+        }
+
+        Some(match self.channel_name(&message.channel_id) {
+            Some(channel) => format!("{} in #{}", message.author, channel),
+            None => message.author.clone(),
+        })
+    }
+
+    /// Muted directly, or a thread inside a muted channel
+    pub fn is_channel_muted(&self, channel_id: &str) -> bool {
+        let settings = &self.config.notifications;
+        settings.is_muted(channel_id)
+            || self.find_channel(channel_id)
+                .and_then(|c| c.parent_id.as_deref())
+                .is_some_and(|parent| settings.is_muted(parent))
+    }
+
+    /// Count an incoming message as unread, unless its channel is open
+    pub fn track_unread(&mut self, message: &Message) {
+        if self.is_own_message(message) || self.selected_channel.as_deref() == Some(message.channel_id.as_str()) {
+            self.read_state.mark_read(&message.channel_id, Some(&message.id));
+            return;
+        }
+
+        let guild_id = message.guild_id.clone()
+            .or_else(|| self.guild_id_for_channel(&message.channel_id));
+        let mentioned = self.mentions_me(message);
+        self.read_state.add_message(&message.channel_id, guild_id.as_deref(), &message.id, mentioned);
+    }
+
+    /// Mark a channel read up to its newest loaded message
+    pub fn mark_channel_read(&mut self, channel_id: &str) {
+        let messages = if self.selected_channel.as_deref() == Some(channel_id) {
+            Some(&self.messages)
+        } else {
+            self.message_cache.get(channel_id)
+        };
+        // Outbox placeholders carry IDs Discord never handed out
+        let latest = messages
+            .and_then(|messages| messages.iter().rev().find(|m| m.is_sent()))
+            .map(|m| m.id.clone());
+        self.read_state.mark_read(channel_id, latest.as_deref());
+    }
+
+    /// Unread counts for a channel, including forum posts when it is a forum
+    pub fn channel_unread(&self, guild_id: &str, channel: &Channel) -> UnreadCount {
+        let mut count = self.read_state.count(&channel.id);
+        if channel.kind == ChannelType::Forum {
+            if let Some(channel_list) = self.channel_cache.get(guild_id) {
+                for post in channel_list.threads_in(&channel.id) {
+                    count += self.read_state.count(&post.id);
+                }
+            }
+        }
+        count
+    }
+
+    /// Unread counts rolled up over the channels and threads in a category
+    pub fn category_unread(&self, guild_id: &str, category_id: &str) -> UnreadCount {
+        let mut count = UnreadCount::default();
+        if let Some(channel_list) = self.channel_cache.get(guild_id) {
+            for channel in channel_list.text_channels(category_id) {
+                count += self.read_state.count(&channel.id);
+                for thread in channel_list.threads_in(&channel.id) {
+                    count += self.read_state.count(&thread.id);
+                }
+            }
+        }
+        count
+    }
+
+    pub fn guild_unread(&self, guild_id: &str) -> UnreadCount {
+        self.read_state.guild_count(guild_id)
+    }
+
+    pub fn dm_unread(&self) -> UnreadCount {
+        let mut count = UnreadCount::default();
+        for dm in &self.dms {
+            count += self.read_state.count(&dm.id);
+        }
+        count
+    }
+
+    /// Add or refresh a thread in an already loaded guild
+    pub fn upsert_thread(&mut self, guild_id: &str, thread: Channel) {
+        if let Some(channel_list) = self.channel_cache.get_mut(guild_id) {
+            channel_list.upsert_thread(thread);
+        }
+    }
+
+    pub fn remove_thread(&mut self, guild_id: &str, thread_id: &str) {
+        if let Some(channel_list) = self.channel_cache.get_mut(guild_id) {
+            channel_list.remove_thread(thread_id);
+        }
+        for posts in self.forum_posts.values_mut() {
+            posts.retain(|post| post.id != thread_id);
+        }
+
+        let items = self.get_sidebar_items();
+        self.selected_sidebar_idx = self.selected_sidebar_idx.min(items.len().saturating_sub(1));
+    }
+
+    /// The sidebar as it stands, for browsing offline next time
+    pub fn archived_session(&self) -> ArchivedSession {
+        ArchivedSession {
+            user_id: self.current_user_id.clone(),
+            user_name: self.current_user_name.clone(),
+            guilds: self.guilds.clone(),
+            dms: self.dms.clone(),
+            channels: self.channel_cache.clone(),
+        }
+    }
+
+    pub fn restore_session(&mut self, session: ArchivedSession) {
+        self.current_user_id = session.user_id;
+        self.current_user_name = session.user_name;
+        self.guilds = session.guilds;
+        self.dms = session.dms;
+        self.channel_cache = session.channels;
+    }
+
+    /// Forget everything about the current account, keeping settings, notifications
+    /// and the images already loaded
+    pub fn reset_for_account(&mut self, account: String) {
+        let placeholder = self.image_renderer.without_caches();
+        let image_renderer = std::mem::replace(&mut self.image_renderer, placeholder);
+
+        let mut fresh = Self::with_image_renderer(self.config.clone(), image_renderer);
+        fresh.notifications = std::mem::take(&mut self.notifications);
+        fresh.desktop_notifier = self.desktop_notifier.take();
+        fresh.account = Some(account);
+        fresh.account_generation = self.account_generation + 1;
+        *self = fresh;
+    }
+
+    /// Accounts offered in the switcher: the configured ones, plus the current one
+    /// when it was picked with `--account` without being listed
+    pub fn account_names(&self) -> Vec<String> {
+        let mut names = self.config.accounts.names.clone();
+        if let Some(account) = &self.account {
+            if !names.contains(account) {
+                names.push(account.clone());
+            }
+        }
+        names
+    }
+
+    /// Show the token screen; it closes back to the sidebar
+    pub fn open_token_setup(&mut self, setup: TokenSetup) {
+        self.token_setup = Some(setup);
+        self.mode = AppMode::TokenSetup;
+    }
+
+    pub fn close_token_setup(&mut self) {
+        self.token_setup = None;
+        self.mode = AppMode::Sidebar;
+    }
+
+    pub fn set_discord_client(&mut self, client: Box<dyn ChatBackend>) {
         self.discord_client = Some(Arc::new(Mutex::new(client)));
     }
 
@@ -190,3 +873,103 @@ impl App {
         }
     }
 }
+
+/// Index just past the newest message Discord knows about, where outbox placeholders begin
+fn after_last_sent(messages: &[Message]) -> usize {
+    messages.iter().rposition(|m| m.is_sent()).map_or(0, |i| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NetworkSettings;
+    use crate::models::{DmUser, MentionedUser};
+
+    fn test_app() -> App {
+        let renderer = ImageRenderer::halfblocks(&NetworkSettings::default());
+        let mut app = App::with_image_renderer(Config::default(), renderer);
+        app.current_user_id = Some("1".to_string());
+        app.dms.push(DmChannel::new("10", DmUser::new("2", "alice", "0")));
+
+        let mut list = ChannelList::new();
+        list.channels.push(Channel::new("20", "general", ChannelType::Text, 0));
+        list.threads.push(Channel::new("21", "plans", ChannelType::Thread, 0).with_parent("20"));
+        app.channel_cache.insert("30".to_string(), list);
+        app
+    }
+
+    fn message(channel_id: &str, content: &str) -> Message {
+        let guild_id = (channel_id != "10").then(|| "30".to_string());
+        Message::new("100", channel_id, "alice", "2", None, content, "").with_guild_id(guild_id)
+    }
+
+    fn mentioning_me(channel_id: &str) -> Message {
+        message(channel_id, "<@1> look").with_mentioned_users(vec![MentionedUser::new("1", "me")])
+    }
+
+    #[test]
+    fn direct_messages_notify_with_the_author_as_title() {
+        let mut app = test_app();
+        assert_eq!(app.desktop_notification_title(&message("10", "hi")).as_deref(), Some("alice"));
+
+        app.config.notifications.direct_messages = false;
+        assert_eq!(app.desktop_notification_title(&message("10", "hi")), None);
+    }
+
+    #[test]
+    fn guild_messages_notify_only_for_mentions_and_keywords() {
+        let mut app = test_app();
+        assert_eq!(app.desktop_notification_title(&message("20", "hi")), None);
+        assert_eq!(app.desktop_notification_title(&mentioning_me("20")).as_deref(), Some("alice in #general"));
+        assert!(app.desktop_notification_title(&message("20", "@everyone hi").with_mentions_everyone(true)).is_some());
+
+        app.config.notifications.keywords = vec!["Deploy".to_string()];
+        assert!(app.desktop_notification_title(&message("20", "deploy is done")).is_some());
+        assert_eq!(app.desktop_notification_title(&message("20", "redeployed it")), None);
+
+        app.config.notifications.mentions = false;
+        assert_eq!(app.desktop_notification_title(&mentioning_me("20")), None);
+    }
+
+    #[test]
+    fn role_mentions_count_only_for_roles_we_hold() {
+        let mut app = test_app();
+        let role_mention = message("20", "<@&40> ping").with_mention_roles(vec!["40".to_string()]);
+        assert_eq!(app.desktop_notification_title(&role_mention), None);
+
+        app.member_roles.insert("30".to_string(), vec!["40".to_string()]);
+        assert!(app.mentions_me(&role_mention));
+        assert!(app.desktop_notification_title(&role_mention).is_some());
+    }
+
+    #[test]
+    fn quiet_when_disabled_muted_open_or_our_own() {
+        let mut app = test_app();
+        app.config.notifications.do_not_disturb = true;
+        assert_eq!(app.desktop_notification_title(&message("10", "hi")), None);
+
+        let mut app = test_app();
+        app.config.notifications.enabled = false;
+        assert_eq!(app.desktop_notification_title(&message("10", "hi")), None);
+
+        let mut app = test_app();
+        app.selected_channel = Some("20".to_string());
+        assert_eq!(app.desktop_notification_title(&mentioning_me("20")), None);
+
+        let app = test_app();
+        let mut own = message("10", "hi");
+        own.author_id = "1".to_string();
+        assert_eq!(app.desktop_notification_title(&own), None);
+    }
+
+    #[test]
+    fn muting_a_channel_silences_its_threads() {
+        let mut app = test_app();
+        app.config.notifications.muted_channels = vec!["20".to_string()];
+        assert_eq!(app.desktop_notification_title(&mentioning_me("20")), None);
+        assert_eq!(app.desktop_notification_title(&mentioning_me("21")), None);
+
+        app.config.notifications.muted_channels = vec!["21".to_string()];
+        assert!(app.desktop_notification_title(&mentioning_me("20")).is_some());
+    }
+}
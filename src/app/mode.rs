@@ -3,6 +3,13 @@ pub enum AppMode {
     Sidebar,
     Messages,
     Input,
+    Reaction,
+    Pins,
+    Search,
+    Forum,
+    ForumCompose,
     Settings,
+    Accounts,
+    TokenSetup,
     KeybindRecording(String),
 }
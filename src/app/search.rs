@@ -0,0 +1,206 @@
+use crate::models::{snowflake, Message, SearchQuery, SearchScope, HAS_FILTERS, SEARCH_PAGE_SIZE};
+use anyhow::{bail, Context, Result};
+use chrono::{Days, NaiveDate};
+
+/// The search overlay: the query being typed and the page of results for the last one run
+#[derive(Debug, Clone)]
+pub struct SearchState {
+    pub scope: SearchScope,
+    pub input: String,
+    /// The query the current results belong to
+    pub query: Option<SearchQuery>,
+    pub results: Vec<Message>,
+    pub total_results: usize,
+    pub page: usize,
+    pub selected_idx: usize,
+    pub loading: bool,
+}
+
+impl SearchState {
+    pub fn new(scope: SearchScope) -> Self {
+        Self {
+            scope,
+            input: String::new(),
+            query: None,
+            results: Vec::new(),
+            total_results: 0,
+            page: 0,
+            selected_idx: 0,
+            loading: false,
+        }
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.total_results.div_ceil(SEARCH_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> usize {
+        self.page * SEARCH_PAGE_SIZE
+    }
+
+    pub fn selected_result(&self) -> Option<&Message> {
+        self.results.get(self.selected_idx)
+    }
+
+    /// Move to another page of the current results and have it fetched
+    pub fn turn_page(&mut self, forward: bool) {
+        let page = if forward {
+            (self.page + 1).min(self.page_count().saturating_sub(1))
+        } else {
+            self.page.saturating_sub(1)
+        };
+        if page != self.page && self.query.is_some() {
+            self.page = page;
+            self.loading = true;
+        }
+    }
+}
+
+/// Parse a query like `deploy from:alice in:#ops has:link after:2024-01-31`.
+/// Names in `from:` and `in:` are looked up with the given resolvers;
+/// values with spaces can be quoted, as in `from:"Alice Smith"`.
+pub fn parse_query(
+    input: &str,
+    resolve_user: impl Fn(&str) -> Option<String>,
+    resolve_channel: impl Fn(&str) -> Option<String>,
+) -> Result<SearchQuery> {
+    let mut query = SearchQuery::default();
+    let mut words = Vec::new();
+
+    for token in tokenize(input) {
+        let Some((filter, value)) = token.split_once(':').filter(|(_, v)| !v.is_empty()) else {
+            words.push(token);
+            continue;
+        };
+
+        match filter {
+            "from" => {
+                let id = resolve_user(value).with_context(|| format!("Unknown user {}", value))?;
+                query.author_ids.push(id);
+            }
+            "in" => {
+                let id = resolve_channel(value).with_context(|| format!("Unknown channel {}", value))?;
+                query.channel_ids.push(id);
+            }
+            "has" => {
+                if !HAS_FILTERS.contains(&value) {
+                    bail!("has: must be one of {}", HAS_FILTERS.join(", "));
+                }
+                query.has.push(value.to_string());
+            }
+            "before" => {
+                query.max_id = Some(snowflake::from_timestamp_ms(day_start_ms(parse_date(value)?)));
+            }
+            "after" => {
+                let next_day = parse_date(value)?
+                    .checked_add_days(Days::new(1))
+                    .context("Date is out of range")?;
+                query.min_id = Some(snowflake::from_timestamp_ms(day_start_ms(next_day)));
+            }
+            _ => words.push(token),
+        }
+    }
+
+    query.content = words.join(" ");
+    if query.is_empty() {
+        bail!("Type something to search for");
+    }
+    Ok(query)
+}
+
+/// Split on whitespace, keeping double-quoted runs together and dropping the quotes
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .with_context(|| format!("Dates are written as YYYY-MM-DD, not {}", value))
+}
+
+fn day_start_ms(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().timestamp_millis())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Result<SearchQuery> {
+        parse_query(
+            input,
+            |name| (name == "Alice Smith" || name == "bob").then(|| format!("user-{}", name.len())),
+            |name| (name == "#ops").then(|| "chan-ops".to_string()),
+        )
+    }
+
+    #[test]
+    fn tokenize_keeps_quoted_runs_together() {
+        assert_eq!(
+            tokenize("  deploy   from:\"Alice Smith\" \"two words\" "),
+            ["deploy", "from:Alice Smith", "two words"]
+        );
+        assert!(tokenize("   ").is_empty());
+    }
+
+    #[test]
+    fn resolves_quoted_and_repeated_filters() {
+        let query = parse("deploy from:\"Alice Smith\" from:bob in:#ops has:link has:file").unwrap();
+        assert_eq!(query.content, "deploy");
+        assert_eq!(query.author_ids, ["user-11", "user-3"]);
+        assert_eq!(query.channel_ids, ["chan-ops"]);
+        assert_eq!(query.has, ["link", "file"]);
+    }
+
+    #[test]
+    fn unknown_filters_and_empty_values_stay_in_the_content() {
+        let query = parse("url:https://example.com from: done").unwrap();
+        assert_eq!(query.content, "url:https://example.com from: done");
+        assert!(query.author_ids.is_empty());
+    }
+
+    #[test]
+    fn reports_unresolved_names_and_invalid_values() {
+        let error = parse("from:carol").unwrap_err().to_string();
+        assert_eq!(error, "Unknown user carol");
+        let error = parse("in:\"#no such channel\"").unwrap_err().to_string();
+        assert_eq!(error, "Unknown channel #no such channel");
+        let error = parse("has:gif").unwrap_err().to_string();
+        assert!(error.starts_with("has: must be one of link, embed"));
+        assert!(parse("before:31/01/2024").is_err());
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn dates_become_snowflake_bounds() {
+        let query = parse("before:2024-01-31 after:2024-01-31").unwrap();
+        // 2024-01-31T00:00:00Z
+        assert_eq!(query.max_id.as_deref().and_then(snowflake::timestamp_ms), Some(1_706_659_200_000));
+        // after: a day covers the whole of it, so the bound is the next midnight
+        assert_eq!(query.min_id.as_deref().and_then(snowflake::timestamp_ms), Some(1_706_745_600_000));
+
+        // 2025-01-01T00:00:00Z
+        let query = parse("after:2024-12-31").unwrap();
+        assert_eq!(query.min_id.as_deref().and_then(snowflake::timestamp_ms), Some(1_735_689_600_000));
+    }
+}
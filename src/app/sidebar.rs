@@ -1,4 +1,4 @@
-use crate::models::{Guild, Channel, DmChannel, ChannelList, ChannelCategory};
+use crate::models::{Guild, Channel, DmChannel, ChannelList, ChannelCategory, ChannelType};
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
@@ -9,6 +9,7 @@ pub enum SidebarItem {
     Server(Guild),
     Category { guild_id: String, category: ChannelCategory, expanded: bool },
     Channel { guild_id: String, channel: Channel },
+    Thread { guild_id: String, thread: Channel },
 }
 
 pub fn get_items(
@@ -48,10 +49,7 @@ pub fn get_items(
             .unwrap_or_default();
         
         for channel in channel_list.uncategorized_channels() {
-            items.push(SidebarItem::Channel {
-                guild_id: guild.id.clone(),
-                channel: channel.clone(),
-            });
+            push_channel(&mut items, &guild.id, channel_list, channel);
         }
         
         for category in &channel_list.categories {
@@ -65,10 +63,7 @@ pub fn get_items(
             
             if is_expanded {
                 for channel in channel_list.text_channels(&category.id) {
-                    items.push(SidebarItem::Channel {
-                        guild_id: guild.id.clone(),
-                        channel: channel.clone(),
-                    });
+                    push_channel(&mut items, &guild.id, channel_list, channel);
                 }
             }
         }
@@ -76,3 +71,28 @@ pub fn get_items(
     
     items
 }
+
+/// Push a channel followed by its active threads. Forum posts are listed
+/// in the forum view instead of the sidebar.
+fn push_channel(
+    items: &mut Vec<SidebarItem>,
+    guild_id: &str,
+    channel_list: &ChannelList,
+    channel: &Channel,
+) {
+    items.push(SidebarItem::Channel {
+        guild_id: guild_id.to_string(),
+        channel: channel.clone(),
+    });
+
+    if channel.kind == ChannelType::Forum {
+        return;
+    }
+
+    for thread in channel_list.threads_in(&channel.id) {
+        items.push(SidebarItem::Thread {
+            guild_id: guild_id.to_string(),
+            thread: thread.clone(),
+        });
+    }
+}
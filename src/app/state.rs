@@ -14,7 +14,7 @@ pub fn get_channel_name(
         
         // Check if it's a guild channel
         for channel_list in channel_cache.values() {
-            if let Some(channel) = channel_list.channels.iter().find(|c| &c.id == id) {
+            if let Some(channel) = channel_list.find(id) {
                 return Some(channel.name.clone());
             }
         }
@@ -36,7 +36,7 @@ pub fn get_guild_name(
         
         // Check guild channels
         for (guild_id, channel_list) in channel_cache {
-            if channel_list.channels.iter().any(|c| &c.id == channel_id) {
+            if channel_list.find(channel_id).is_some() {
                 return guilds.iter()
                     .find(|g| &g.id == guild_id)
                     .map(|g| g.name.clone());
@@ -45,3 +45,13 @@ pub fn get_guild_name(
         None
     })
 }
+
+pub fn get_guild_id(
+    channel_id: &str,
+    channel_cache: &HashMap<String, ChannelList>,
+) -> Option<String> {
+    channel_cache
+        .iter()
+        .find(|(_, channel_list)| channel_list.find(channel_id).is_some())
+        .map(|(guild_id, _)| guild_id.clone())
+}
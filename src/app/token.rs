@@ -0,0 +1,40 @@
+/// The token screen: a token being pasted for an account, shown masked
+#[derive(Debug, Clone)]
+pub struct TokenSetup {
+    pub account: String,
+    pub input: String,
+    /// Why there is no usable token yet, or why the last one was turned down
+    pub status: Option<String>,
+    /// Sign in as `account` once its token is saved
+    pub connect: bool,
+    /// Set on submit, cleared once `/users/@me` has answered
+    pub validating: bool,
+}
+
+impl TokenSetup {
+    pub fn new(account: impl Into<String>, connect: bool) -> Self {
+        Self {
+            account: account.into(),
+            input: String::new(),
+            status: None,
+            connect,
+            validating: false,
+        }
+    }
+
+    pub fn with_status(mut self, status: impl Into<String>) -> Self {
+        self.status = Some(status.into());
+        self
+    }
+
+    /// The input as bullets followed by its length, cut to fit `width` columns
+    pub fn masked_input(&self, width: usize) -> String {
+        let count = self.input.chars().count();
+        if count == 0 {
+            return "_".to_string();
+        }
+        let suffix = format!("_ ({} characters)", count);
+        let bullets = count.min(width.saturating_sub(suffix.chars().count()));
+        format!("{}{}", "•".repeat(bullets), suffix)
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::{Duration, Instant};
+
+/// How long a typing indicator lasts without a new trigger
+pub const TYPING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often we tell Discord that we are still typing
+pub const TYPING_TRIGGER_INTERVAL: Duration = Duration::from_secs(8);
+
+/// Someone currently typing in a channel
+#[derive(Debug, Clone)]
+pub struct TypingUser {
+    pub channel_id: String,
+    pub user_id: String,
+    pub name: String,
+    pub started: Instant,
+}
+
+impl TypingUser {
+    pub fn new(channel_id: impl Into<String>, user_id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            channel_id: channel_id.into(),
+            user_id: user_id.into(),
+            name: name.into(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.started.elapsed() >= TYPING_TIMEOUT
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::ops::AddAssign;
+
+/// Unread messages and mentions in a channel, or summed over several
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnreadCount {
+    pub messages: usize,
+    pub mentions: usize,
+}
+
+impl UnreadCount {
+    pub fn is_unread(&self) -> bool {
+        self.messages > 0
+    }
+}
+
+impl AddAssign for UnreadCount {
+    fn add_assign(&mut self, other: Self) {
+        self.messages += other.messages;
+        self.mentions += other.mentions;
+    }
+}
+
+/// Last-read position and unread counts per channel
+#[derive(Debug, Default)]
+pub struct ReadState {
+    last_read: HashMap<String, String>,
+    counts: HashMap<String, UnreadCount>,
+    channel_guilds: HashMap<String, String>,
+}
+
+impl ReadState {
+    /// Count a message that arrived while its channel was not being viewed.
+    /// Messages at or before the last-read one are ignored.
+    pub fn add_message(&mut self, channel_id: &str, guild_id: Option<&str>, message_id: &str, mentioned: bool) {
+        if self.is_read(channel_id, message_id) {
+            return;
+        }
+
+        let count = self.counts.entry(channel_id.to_string()).or_default();
+        count.messages += 1;
+        if mentioned {
+            count.mentions += 1;
+        }
+
+        if let Some(guild_id) = guild_id {
+            self.channel_guilds.insert(channel_id.to_string(), guild_id.to_string());
+        }
+    }
+
+    /// Mark a channel read up to `message_id`, clearing its counts
+    pub fn mark_read(&mut self, channel_id: &str, message_id: Option<&str>) {
+        self.counts.remove(channel_id);
+
+        if let Some(message_id) = message_id {
+            if !self.is_read(channel_id, message_id) {
+                self.last_read.insert(channel_id.to_string(), message_id.to_string());
+            }
+        }
+    }
+
+    pub fn count(&self, channel_id: &str) -> UnreadCount {
+        self.counts.get(channel_id).copied().unwrap_or_default()
+    }
+
+    /// Counts summed over every channel and thread seen in a guild
+    pub fn guild_count(&self, guild_id: &str) -> UnreadCount {
+        let mut total = UnreadCount::default();
+        for (channel_id, count) in &self.counts {
+            if self.channel_guilds.get(channel_id).map(String::as_str) == Some(guild_id) {
+                total += *count;
+            }
+        }
+        total
+    }
+
+    fn is_read(&self, channel_id: &str, message_id: &str) -> bool {
+        let Some(last_read) = self.last_read.get(channel_id) else {
+            return false;
+        };
+
+        // Snowflakes grow over time, so compare them numerically
+        match (last_read.parse::<u64>(), message_id.parse::<u64>()) {
+            (Ok(last_read), Ok(message_id)) => message_id <= last_read,
+            _ => last_read == message_id,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::config::DEFAULT_ACCOUNT;
+use crate::models::{snowflake, ChannelList, DmChannel, Guild, Message};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
+use tokio::fs;
+
+// Only the newest messages of each channel are kept
+const MAX_ARCHIVED_MESSAGES: usize = 2000;
+
+/// What the sidebar needs to be usable without a connection. Like every model stored
+/// in the archive it takes `#[serde(default)]`, so archives from before a field was
+/// added still load.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchivedSession {
+    pub user_id: Option<String>,
+    pub user_name: Option<String>,
+    pub guilds: Vec<Guild>,
+    pub dms: Vec<DmChannel>,
+    pub channels: HashMap<String, ChannelList>,
+}
+
+/// Messages we have fetched or received, kept under the cache dir as one JSON file
+/// per channel, oldest first. Each account has its own.
+pub struct MessageArchive {
+    dir: PathBuf,
+    /// Hash of what was last saved under each name, so unchanged data is not rewritten
+    saved: HashMap<String, u64>,
+}
+
+impl MessageArchive {
+    pub fn open(account: &str) -> Result<Self> {
+        let mut dir = dirs::cache_dir()
+            .context("Could not find cache directory")?
+            .join("remycord")
+            .join("archive");
+        // The default account keeps the archive it had before accounts existed
+        if account != DEFAULT_ACCOUNT {
+            dir = dir.join("accounts").join(account);
+        }
+        std::fs::create_dir_all(dir.join("channels"))?;
+        Ok(Self { dir, saved: HashMap::new() })
+    }
+
+    /// The newest `limit` archived messages of a channel
+    pub async fn load_recent(&self, channel_id: &str, limit: usize) -> Result<Vec<Message>> {
+        let mut messages = self.load_channel(channel_id).await?;
+        messages.drain(..messages.len().saturating_sub(limit));
+        Ok(messages)
+    }
+
+    /// Up to `limit` archived messages older than `before`
+    pub async fn load_before(&self, channel_id: &str, before: &str, limit: usize) -> Result<Vec<Message>> {
+        let before = snowflake::value(before);
+        let mut messages = self.load_channel(channel_id).await?;
+        messages.retain(|m| snowflake::value(&m.id) < before);
+        messages.drain(..messages.len().saturating_sub(limit));
+        Ok(messages)
+    }
+
+    /// Merge the newest stretch of a channel's history into its archive. See `merge_history`.
+    pub async fn save_channel(&mut self, channel_id: &str, recent: &[Message]) -> Result<()> {
+        let name = format!("channels/{}.json", channel_id);
+        let recent: Vec<Message> = recent.iter().filter(|m| m.send_state.is_none()).cloned().collect();
+        let hash = hash_of(&serde_json::to_vec(&recent)?);
+        if self.saved.get(&name) == Some(&hash) {
+            return Ok(());
+        }
+
+        let mut messages = merge_history(self.load_channel(channel_id).await?, recent);
+        messages.drain(..messages.len().saturating_sub(MAX_ARCHIVED_MESSAGES));
+        self.write(&name, &serde_json::to_vec(&messages)?).await?;
+        self.saved.insert(name, hash);
+        Ok(())
+    }
+
+    /// Forget a channel's archive, for when it can no longer be joined up with the server
+    pub async fn clear_channel(&mut self, channel_id: &str) -> Result<()> {
+        let name = format!("channels/{}.json", channel_id);
+        self.saved.remove(&name);
+        match fs::remove_file(self.dir.join(&name)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn load_session(&self) -> Result<Option<ArchivedSession>> {
+        let path = self.dir.join("session.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let bytes = fs::read(&path).await?;
+        Ok(Some(serde_json::from_slice(&bytes).context("Archived session is corrupt")?))
+    }
+
+    pub async fn save_session(&mut self, session: &ArchivedSession) -> Result<()> {
+        let bytes = serde_json::to_vec(session)?;
+        let hash = hash_of(&bytes);
+        if self.saved.get("session.json") == Some(&hash) {
+            return Ok(());
+        }
+        self.write("session.json", &bytes).await?;
+        self.saved.insert("session.json".to_string(), hash);
+        Ok(())
+    }
+
+    async fn load_channel(&self, channel_id: &str) -> Result<Vec<Message>> {
+        let path = self.dir.join("channels").join(format!("{}.json", channel_id));
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let bytes = fs::read(&path).await?;
+        serde_json::from_slice(&bytes)
+            .with_context(|| format!("Archive for channel {} is corrupt", channel_id))
+    }
+
+    // Written to a temporary file first so a crash never leaves a half-written archive
+    async fn write(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.dir.join(name);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, bytes).await?;
+        fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+}
+
+/// Lay a fresh stretch of history over archived messages. `recent` runs, oldest first,
+/// from inside or just after the archived span up to the newest message, and replaces
+/// whatever the archive holds from its first message on, so edits and deletions stick.
+pub fn merge_history(archived: Vec<Message>, recent: Vec<Message>) -> Vec<Message> {
+    let Some(start) = recent.first().map(|m| snowflake::value(&m.id)) else {
+        return archived;
+    };
+    let mut messages: Vec<Message> = archived
+        .into_iter()
+        .filter(|m| snowflake::value(&m.id) < start)
+        .chain(recent)
+        .collect();
+    messages.sort_by_key(|m| snowflake::value(&m.id));
+    messages.dedup_by(|a, b| a.id == b.id);
+    messages
+}
+
+fn hash_of(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
@@ -2,6 +2,7 @@ use crossterm::event::{KeyCode, KeyModifiers};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Keybinds {
     pub quit: KeyBind,
     pub settings: KeyBind,
@@ -19,6 +20,21 @@ pub struct Keybinds {
     pub cursor_right: KeyBind,
     pub cursor_start: KeyBind,
     pub cursor_end: KeyBind,
+    pub edit_message: KeyBind,
+    pub delete_message: KeyBind,
+    pub reply: KeyBind,
+    pub toggle_reply_mention: KeyBind,
+    pub react: KeyBind,
+    pub new_post: KeyBind,
+    pub retry_send: KeyBind,
+    pub mute_channel: KeyBind,
+    pub toggle_dnd: KeyBind,
+    pub pins: KeyBind,
+    pub toggle_pin: KeyBind,
+    pub search: KeyBind,
+    pub switch_account: KeyBind,
+    pub set_token: KeyBind,
+    pub remove_token: KeyBind,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +62,21 @@ impl Default for Keybinds {
             cursor_right: KeyBind::new("Right", vec![]),
             cursor_start: KeyBind::new("a", vec!["Ctrl"]),
             cursor_end: KeyBind::new("e", vec!["Ctrl"]),
+            edit_message: KeyBind::new("e", vec![]),
+            delete_message: KeyBind::new("d", vec![]),
+            reply: KeyBind::new("r", vec![]),
+            toggle_reply_mention: KeyBind::new("p", vec!["Ctrl"]),
+            react: KeyBind::new("+", vec![]),
+            new_post: KeyBind::new("n", vec![]),
+            retry_send: KeyBind::new("t", vec![]),
+            mute_channel: KeyBind::new("m", vec![]),
+            toggle_dnd: KeyBind::new("z", vec![]),
+            pins: KeyBind::new("p", vec![]),
+            toggle_pin: KeyBind::new("P", vec!["Shift"]),
+            search: KeyBind::new("/", vec![]),
+            switch_account: KeyBind::new("A", vec!["Shift"]),
+            set_token: KeyBind::new("e", vec![]),
+            remove_token: KeyBind::new("d", vec![]),
         }
     }
 }
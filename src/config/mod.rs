@@ -9,10 +9,23 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+pub const DEFAULT_API_BASE: &str = "https://discord.com";
+pub const DEFAULT_GATEWAY_URL: &str = "wss://gateway.discord.gg";
+pub const DEFAULT_CDN_BASE: &str = "https://cdn.discordapp.com";
+pub const DEFAULT_MEDIA_BASE: &str = "https://media.discordapp.net";
+/// The keyring name earlier versions stored their single token under
+pub const DEFAULT_ACCOUNT: &str = "token";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub general: GeneralSettings,
     pub images: ImageSettings,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub accounts: AccountSettings,
     pub theme_name: String,
     pub keybinds: Keybinds,
     #[serde(skip)]
@@ -50,6 +63,42 @@ pub struct ImageSettings {
     pub cache_warn_threshold_percent: u8,
 }
 
+/// Which messages raise a desktop notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    pub direct_messages: bool,
+    pub mentions: bool,
+    /// Case-insensitive words that notify wherever they are said
+    pub keywords: Vec<String>,
+    pub do_not_disturb: bool,
+    pub muted_channels: Vec<String>,
+}
+
+/// Where remycord talks to Discord
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// Scheme and host of the HTTP API; requests go to `{api_base}/api/v10/...`
+    pub api_base: String,
+    /// WebSocket URL of the gateway, without query parameters
+    pub gateway_url: String,
+    /// Scheme and host serving avatars, emoji and server icons
+    pub cdn_base: String,
+    /// Scheme and host serving stickers, which include animated GIFs the CDN lacks
+    pub media_base: String,
+}
+
+/// Accounts to offer in the switcher, each with its token in the keyring under its name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountSettings {
+    /// Used when `--account` is not given
+    pub default: String,
+    pub names: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CacheAutoClear {
     Never,
@@ -71,6 +120,9 @@ impl Default for Config {
         Self {
             general: GeneralSettings::default(),
             images: ImageSettings::default(),
+            notifications: NotificationSettings::default(),
+            network: NetworkSettings::default(),
+            accounts: AccountSettings::default(),
             theme_name: "oxocarbon-dark".to_string(),
             keybinds: Keybinds::default(),
             theme: Theme::default(),
@@ -91,6 +143,94 @@ impl Default for GeneralSettings {
     }
 }
 
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            direct_messages: true,
+            mentions: true,
+            keywords: Vec::new(),
+            do_not_disturb: false,
+            muted_channels: Vec::new(),
+        }
+    }
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            api_base: DEFAULT_API_BASE.to_string(),
+            gateway_url: DEFAULT_GATEWAY_URL.to_string(),
+            cdn_base: DEFAULT_CDN_BASE.to_string(),
+            media_base: DEFAULT_MEDIA_BASE.to_string(),
+        }
+    }
+}
+
+impl Default for AccountSettings {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_ACCOUNT.to_string(),
+            names: vec![DEFAULT_ACCOUNT.to_string()],
+        }
+    }
+}
+
+impl AccountSettings {
+    /// Refuse names that would lead the archive outside its directory
+    pub fn validate(&self) -> Result<()> {
+        for name in std::iter::once(&self.default).chain(&self.names) {
+            check_account_name(name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Account names become directory names under the archive, so they must stay a single
+/// path component
+pub fn check_account_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name == "." || name.contains(['/', '\\']) || name.contains("..") {
+        anyhow::bail!("Account name '{}' must be a plain name, without '/', '\\' or '..'", name);
+    }
+    Ok(())
+}
+
+impl NotificationSettings {
+    pub fn is_muted(&self, channel_id: &str) -> bool {
+        self.muted_channels.iter().any(|id| id == channel_id)
+    }
+
+    /// Mute or unmute a channel, returning whether it is now muted
+    pub fn toggle_mute(&mut self, channel_id: &str) -> bool {
+        if self.is_muted(channel_id) {
+            self.muted_channels.retain(|id| id != channel_id);
+            false
+        } else {
+            self.muted_channels.push(channel_id.to_string());
+            true
+        }
+    }
+
+    /// Whether any keyword appears in `text` as a whole word
+    pub fn matches_keyword(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.keywords
+            .iter()
+            .filter(|k| !k.trim().is_empty())
+            .any(|k| contains_word(&text, &k.trim().to_lowercase()))
+    }
+}
+
+// `word` somewhere in `text` with no letter, digit or underscore right before or after it
+fn contains_word(text: &str, word: &str) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
 impl Default for ImageSettings {
     fn default() -> Self {
         let in_kitty = std::env::var("TERM")
@@ -188,6 +328,7 @@ pub fn load_config() -> Result<Config> {
     if config_path.exists() {
         let contents = fs::read_to_string(&config_path)?;
         let mut config: Config = toml::from_str(&contents)?;
+        config.accounts.validate()?;
         
         config.theme = load_theme(&config.theme_name).unwrap_or_else(|_| {
             eprintln!("Warning: Could not load theme '{}', using default", config.theme_name);
@@ -210,3 +351,50 @@ pub fn save_config(config: &Config) -> Result<()> {
     fs::write(config_path, contents)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords(words: &[&str]) -> NotificationSettings {
+        NotificationSettings {
+            keywords: words.iter().map(|w| w.to_string()).collect(),
+            ..NotificationSettings::default()
+        }
+    }
+
+    #[test]
+    fn keywords_match_whole_words_in_any_case() {
+        let settings = keywords(&["Deploy", "release party"]);
+        assert!(settings.matches_keyword("deploy"));
+        assert!(settings.matches_keyword("Time to DEPLOY!"));
+        assert!(settings.matches_keyword("(deploy)"));
+        assert!(settings.matches_keyword("see you at the release party tonight"));
+        assert!(settings.matches_keyword("redeployed, then deploy again"));
+    }
+
+    #[test]
+    fn keywords_do_not_match_inside_other_words() {
+        let settings = keywords(&["deploy"]);
+        assert!(!settings.matches_keyword("redeployed"));
+        assert!(!settings.matches_keyword("deploy_bot"));
+        assert!(!settings.matches_keyword("deployment"));
+        assert!(!settings.matches_keyword(""));
+    }
+
+    #[test]
+    fn blank_keywords_never_match() {
+        assert!(!keywords(&["", "  "]).matches_keyword("anything at all"));
+    }
+
+    #[test]
+    fn account_names_stay_one_path_component() {
+        for name in ["token", "work", "alt.account", "me@home"] {
+            assert!(check_account_name(name).is_ok(), "{}", name);
+        }
+        for name in ["", " ", ".", "..", "../x", "a/b", "a\\b", "x..y"] {
+            assert!(check_account_name(name).is_err(), "{}", name);
+        }
+        assert!(AccountSettings::default().validate().is_ok());
+    }
+}
@@ -1,8 +1,17 @@
 use anyhow::Result;
+use serde::Deserialize;
+use serenity::async_trait;
 use serenity::prelude::*;
 use serenity::model::prelude::*;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+
+use super::backend::ChatBackend;
+use super::connection::{ConnectionState, ReconnectPolicy, STABLE_CONNECTION};
+
+use crate::config::NetworkSettings;
 
 use crate::models::{
     Guild as AppGuild, 
@@ -11,64 +20,195 @@ use crate::models::{
     ChannelCategory,
     ChannelType, 
     Message as AppMessage,
+    Embed as AppEmbed,
+    EmbedField as AppEmbedField,
+    EmbedImage as AppEmbedImage,
     MessageAttachment as AppAttachment,
+    MentionedUser,
+    ReferencedMessage as AppReferencedMessage,
+    Reaction as AppReaction,
+    Sticker as AppSticker,
+    StickerFormat,
+    ReactionEmoji,
+    OutgoingMessage,
+    SearchPage,
+    SearchQuery,
+    SearchScope,
     DmChannel as AppDmChannel,
     DmUser,
+    ForumPost,
+    ForumTag as AppForumTag,
 };
 
+// How long to wait on the API at startup before going offline
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone)]
 pub enum DiscordEvent {
     Ready(Vec<AppGuild>),
-    Connected(String),
+    Connected(String, String),
     DmChannels(Vec<AppDmChannel>),
     GuildChannels(String, Vec<AppChannel>),
     Messages(String, Vec<AppMessage>),
     NewMessage(AppMessage),
+    MessageEdited(String, String, String),
+    EmbedsUpdated(String, String, Vec<AppEmbed>),
+    MessageDeleted(String, String),
+    ReactionAdded(String, String, ReactionEmoji, String),
+    ReactionRemoved(String, String, ReactionEmoji, String),
+    ReactionsCleared(String, String, Option<ReactionEmoji>),
+    ThreadUpdated(String, AppChannel),
+    ThreadDeleted(String, String),
+    TypingStarted(String, String, Option<String>),
+    PinsUpdated(String),
+    ConnectionChanged(ConnectionState),
+    MessageSent(String, AppMessage),
+    SendFailed(String, String, String),
     Error(String),
 }
 
+#[derive(Clone)]
 pub struct DiscordClient {
     http: Arc<serenity::http::Http>,
+    /// For endpoints serenity does not cover, such as search
+    rest: reqwest::Client,
+    api_base: String,
+    gateway_url: String,
+    token: String,
     event_tx: mpsc::UnboundedSender<DiscordEvent>,
+    /// Set once to stop the gateway. Dropping the last client stops it too.
+    shutdown: Arc<watch::Sender<bool>>,
+}
+
+/// Response of the message search endpoints. Each hit comes wrapped in its own list.
+#[derive(Deserialize)]
+struct SearchResponse {
+    total_results: usize,
+    messages: Vec<Vec<serenity::model::channel::Message>>,
 }
 
 impl DiscordClient {
-    pub async fn new(token: String) -> Result<(Self, mpsc::UnboundedReceiver<DiscordEvent>)> {
+    /// Talks to the API at `network.api_base` and the gateway at `network.gateway_url`
+    pub async fn new(token: String, network: &NetworkSettings) -> Result<(Self, mpsc::UnboundedReceiver<DiscordEvent>)> {
         let (event_tx, event_rx) = mpsc::unbounded_channel();
         
-        let http = Arc::new(serenity::http::Http::new(&token));
+        let api_base = network.api_base.trim_end_matches('/').to_string();
+        let http = Arc::new(build_http(&token, &api_base));
         
         let client = Self {
             http,
+            rest: reqwest::Client::new(),
+            api_base,
+            gateway_url: network.gateway_url.trim_end_matches('/').to_string(),
+            token,
             event_tx,
+            shutdown: Arc::new(watch::channel(false).0),
         };
         
         Ok((client, event_rx))
     }
     
-    pub async fn start_gateway(&self, token: String) -> Result<()> {
-        let intents = GatewayIntents::GUILDS 
-            | GatewayIntents::GUILD_MESSAGES 
-            | GatewayIntents::DIRECT_MESSAGES
-            | GatewayIntents::MESSAGE_CONTENT;
+    /// Check a token against `/users/@me`, returning the name of the user it belongs to
+    pub async fn validate_token(token: &str, network: &NetworkSettings) -> Result<String> {
+        use serenity::http::HttpError;
         
-        let event_tx = self.event_tx.clone();
-        let handler = Handler { event_tx };
+        let http = build_http(token, network.api_base.trim_end_matches('/'));
+        match tokio::time::timeout(REACHABILITY_TIMEOUT, http.get_current_user()).await {
+            Ok(Ok(user)) => Ok(user.name.clone()),
+            Ok(Err(serenity::Error::Http(HttpError::Request(e)))) => {
+                anyhow::bail!("Could not reach Discord: {}", e)
+            }
+            Ok(Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(response)))) => {
+                // Only a 401 says anything about the token; rate limits and outages do not
+                if response.status_code.as_u16() == 401 {
+                    anyhow::bail!("Discord rejected this token")
+                }
+                anyhow::bail!("Discord answered {}: {}", response.status_code, response.error.message)
+            }
+            Ok(Err(e)) => anyhow::bail!("Could not check the token: {}", e),
+            Err(_) => anyhow::bail!("Discord did not answer in time"),
+        }
+    }
+    
+    pub async fn send_message(&self, outgoing: &OutgoingMessage) -> Result<AppMessage> {
+        use serenity::builder::{CreateAllowedMentions, CreateAttachment, CreateMessage};
         
-        let mut client = Client::builder(token, intents)
-            .event_handler(handler)
-            .await?;
+        let channel_id = outgoing.channel_id.parse::<ChannelId>()?;
         
-        tokio::spawn(async move {
-            if let Err(why) = client.start().await {
-                eprintln!("Client error: {:?}", why);
-            }
-        });
+        let mut attachments = Vec::new();
+        for path in &outgoing.file_paths {
+            let file_data = tokio::fs::read(path).await?;
+            let filename = std::path::Path::new(path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("file")
+                .to_string();
+            
+            attachments.push(CreateAttachment::bytes(file_data, filename));
+        }
         
+        // Discord drops a retry whose nonce it has already seen and returns the original
+        let mut builder = CreateMessage::new()
+            .nonce(Nonce::String(outgoing.nonce.clone()))
+            .enforce_nonce(true);
+        
+        if !outgoing.content.is_empty() {
+            builder = builder.content(&outgoing.content);
+        }
+        
+        for attachment in attachments {
+            builder = builder.add_file(attachment);
+        }
+        
+        if let Some(reply) = &outgoing.reply_to {
+            let message_id = reply.message_id.parse::<MessageId>()?;
+            builder = builder
+                .reference_message((channel_id, message_id))
+                .allowed_mentions(
+                    CreateAllowedMentions::new()
+                        .all_users(true)
+                        .all_roles(true)
+                        .everyone(true)
+                        .replied_user(reply.mention),
+                );
+        }
+        
+        let message = channel_id.send_message(&self.http, builder).await?;
+        
+        Ok(to_app_message(message))
+    }
+}
+
+#[async_trait]
+impl ChatBackend for DiscordClient {
+    /// Whether the API answers at all. A rejected token still counts as reachable;
+    /// only a failed or timed out connection does not.
+    async fn is_reachable(&self) -> bool {
+        use serenity::http::HttpError;
+        
+        match tokio::time::timeout(REACHABILITY_TIMEOUT, self.http.get_current_user()).await {
+            Ok(Err(serenity::Error::Http(HttpError::Request(_)))) | Err(_) => false,
+            Ok(_) => true,
+        }
+    }
+    
+    /// Run the gateway in the background, restarting it with backoff when it fails
+    async fn start_gateway(&self) -> Result<()> {
+        tokio::spawn(run_gateway(
+            self.token.clone(),
+            self.api_base.clone(),
+            self.gateway_url.clone(),
+            self.event_tx.clone(),
+            self.shutdown.subscribe(),
+        ));
         Ok(())
     }
     
-    pub async fn fetch_dms(&self) -> Result<Vec<AppDmChannel>> {
+    async fn shutdown(&self) {
+        self.shutdown.send_replace(true);
+    }
+    
+    async fn fetch_dms(&self) -> Result<Vec<AppDmChannel>> {
         let channels = self.http.get_user_dm_channels().await?;
         
         let dm_channels: Vec<AppDmChannel> = channels
@@ -96,7 +236,7 @@ impl DiscordClient {
         Ok(dm_channels)
     }
     
-    pub async fn fetch_channels(&self, guild_id: &str) -> Result<ChannelList> {
+    async fn fetch_channels(&self, guild_id: &str) -> Result<ChannelList> {
         let guild_id = guild_id.parse::<GuildId>()?;
         let channels = self.http.get_channels(guild_id).await?;
         
@@ -159,10 +299,35 @@ impl DiscordClient {
                     }
                     channel_list.channels.push(ch);
                 }
+                serenity::model::channel::ChannelType::Forum => {
+                    let tags: Vec<AppForumTag> = channel.available_tags
+                        .into_iter()
+                        .map(|t| AppForumTag::new(
+                            t.id.to_string(),
+                            t.name,
+                            match t.emoji {
+                                Some(ForumEmoji::Name(name)) => Some(name),
+                                _ => None,
+                            },
+                        ))
+                        .collect();
+                    let mut ch = AppChannel::new(
+                        channel.id.to_string(),
+                        channel.name,
+                        ChannelType::Forum,
+                        i32::from(channel.position),
+                    ).with_tags(tags);
+                    if let Some(parent) = channel.parent_id {
+                        ch = ch.with_parent(parent.to_string());
+                    }
+                    channel_list.channels.push(ch);
+                }
                 serenity::model::channel::ChannelType::PublicThread
                 | serenity::model::channel::ChannelType::PrivateThread
                 | serenity::model::channel::ChannelType::NewsThread => {
-                    continue;
+                    if let Some(thread) = to_app_thread(channel) {
+                        channel_list.threads.push(thread);
+                    }
                 }
                 _ => {
                     continue;
@@ -170,127 +335,607 @@ impl DiscordClient {
             }
         }
         
+        // Threads aren't part of the channel listing; fetch the active ones separately
+        if let Ok(active) = self.http.get_guild_active_threads(guild_id).await {
+            for thread in active.threads.into_iter().filter_map(to_app_thread) {
+                if !channel_list.threads.iter().any(|t| t.id == thread.id) {
+                    channel_list.threads.push(thread);
+                }
+            }
+        }
+        
         channel_list.sort();
         
         Ok(channel_list)
     }
     
-    pub async fn fetch_messages(&self, channel_id: &str, limit: u8) -> Result<Vec<AppMessage>> {
+    /// Active and recently archived posts in a forum, most recently active first
+    async fn fetch_forum_posts(&self, guild_id: &str, forum_id: &str) -> Result<Vec<ForumPost>> {
+        let guild_id = guild_id.parse::<GuildId>()?;
+        let forum_id = forum_id.parse::<ChannelId>()?;
+        
+        let mut posts: Vec<ForumPost> = Vec::new();
+        
+        let active = self.http.get_guild_active_threads(guild_id).await?;
+        let archived = self.http
+            .get_channel_archived_public_threads(forum_id, None, Some(50))
+            .await
+            .map(|data| data.threads)
+            .unwrap_or_default();
+        
+        for thread in active.threads.into_iter().chain(archived) {
+            if thread.parent_id != Some(forum_id) {
+                continue;
+            }
+            let post = to_forum_post(thread);
+            if !posts.iter().any(|p| p.id == post.id) {
+                posts.push(post);
+            }
+        }
+        
+        posts.sort_by_key(|p| std::cmp::Reverse(p.last_activity_ms()));
+        
+        Ok(posts)
+    }
+    
+    async fn create_forum_post(
+        &self,
+        forum_id: &str,
+        title: &str,
+        content: &str,
+        tag_ids: &[String],
+    ) -> Result<ForumPost> {
+        use serenity::builder::{CreateForumPost, CreateMessage};
+        
+        let forum_id = forum_id.parse::<ChannelId>()?;
+        
+        let mut builder = CreateForumPost::new(title, CreateMessage::new().content(content));
+        for tag_id in tag_ids {
+            builder = builder.add_applied_tag(tag_id.parse::<ForumTagId>()?);
+        }
+        
+        let thread = forum_id.create_forum_post(&self.http, builder).await?;
+        
+        Ok(to_forum_post(thread))
+    }
+    
+    /// Fetch up to `limit` messages, oldest first, optionally only those older than `before`
+    async fn fetch_messages(
+        &self,
+        channel_id: &str,
+        limit: u8,
+        before: Option<&str>,
+    ) -> Result<Vec<AppMessage>> {
+        use serenity::http::MessagePagination;
+        
         let channel_id = channel_id.parse::<ChannelId>()?;
-        let messages = self.http.get_messages(channel_id, None, Some(limit)).await?;
+        let target = match before {
+            Some(id) => Some(MessagePagination::Before(id.parse::<MessageId>()?)),
+            None => None,
+        };
+        let messages = self.http.get_messages(channel_id, target, Some(limit)).await?;
         
         let mut app_messages: Vec<AppMessage> = messages
             .into_iter()
-            .map(|m| {
-                let timestamp = m.timestamp.format("%H:%M:%S").to_string();
-                
-                let attachments: Vec<AppAttachment> = m.attachments
-                    .into_iter()
-                    .map(|a| AppAttachment::new(
-                        a.id.to_string(),
-                        a.filename,
-                        a.url,
-                        a.proxy_url,
-                        a.width,
-                        a.height,
-                        a.content_type,
-                    ))
-                    .collect();
-                
-                AppMessage::new(
-                    m.id.to_string(),
-                    channel_id.to_string(),
-                    m.author.name,
-                    m.author.id.to_string(),
-                    m.author.avatar.map(|h| h.to_string()),
-                    m.content,
-                    timestamp,
-                ).with_attachments(attachments)
-            })
+            .map(to_app_message)
             .collect();
         
         app_messages.reverse();
         Ok(app_messages)
     }
     
-    pub async fn send_message(&self, channel_id: &str, content: &str) -> Result<AppMessage> {
+    /// Messages sent after `after`, oldest first
+    async fn fetch_messages_after(
+        &self,
+        channel_id: &str,
+        limit: u8,
+        after: &str,
+    ) -> Result<Vec<AppMessage>> {
+        use serenity::http::MessagePagination;
+        
         let channel_id = channel_id.parse::<ChannelId>()?;
+        let target = MessagePagination::After(after.parse::<MessageId>()?);
+        let messages = self.http.get_messages(channel_id, Some(target), Some(limit)).await?;
         
-        let message = channel_id.say(&self.http, content).await?;
+        let mut app_messages: Vec<AppMessage> = messages
+            .into_iter()
+            .map(to_app_message)
+            .collect();
         
-        let timestamp = message.timestamp.format("%H:%M:%S").to_string();
-        Ok(AppMessage::new(
-            message.id.to_string(),
-            channel_id.to_string(),
-            message.author.name,
-            message.author.id.to_string(),
-            message.author.avatar.map(|h| h.to_string()),
-            message.content,
-            timestamp,
-        ))
+        // Discord returns newest first regardless of direction
+        app_messages.sort_by_key(|m| m.id.parse::<u64>().unwrap_or(0));
+        Ok(app_messages)
+    }
+    
+    /// Send an outbox message in the background. The result comes back as
+    /// `MessageSent` or `SendFailed` on the event channel.
+    fn spawn_send(&self, outgoing: OutgoingMessage) {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let event = match client.send_message(&outgoing).await {
+                Ok(message) => DiscordEvent::MessageSent(outgoing.nonce, message),
+                Err(e) => DiscordEvent::SendFailed(outgoing.channel_id, outgoing.nonce, e.to_string()),
+            };
+            let _ = client.event_tx.send(event);
+        });
     }
     
-    pub async fn send_message_with_files(
+    async fn edit_message(
         &self,
         channel_id: &str,
+        message_id: &str,
         content: &str,
-        file_paths: &[String],
     ) -> Result<AppMessage> {
-        use serenity::builder::{CreateAttachment, CreateMessage};
+        use serenity::builder::EditMessage;
         
         let channel_id = channel_id.parse::<ChannelId>()?;
+        let message_id = message_id.parse::<MessageId>()?;
         
-        let mut attachments = Vec::new();
-        for path in file_paths {
-            let file_data = tokio::fs::read(path).await?;
-            let filename = std::path::Path::new(path)
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("file")
-                .to_string();
-            
-            attachments.push(CreateAttachment::bytes(file_data, filename));
+        let message = channel_id
+            .edit_message(&self.http, message_id, EditMessage::new().content(content))
+            .await?;
+        
+        Ok(to_app_message(message))
+    }
+    
+    async fn fetch_user_name(&self, user_id: &str) -> Result<String> {
+        let user_id = user_id.parse::<UserId>()?;
+        let user = self.http.get_user(user_id).await?;
+        
+        Ok(user.display_name().to_string())
+    }
+    
+    async fn fetch_channel_name(&self, channel_id: &str) -> Result<String> {
+        let channel_id = channel_id.parse::<ChannelId>()?;
+        
+        let Channel::Guild(channel) = self.http.get_channel(channel_id).await? else {
+            anyhow::bail!("Channel {} is not a guild channel", channel_id);
+        };
+        
+        Ok(channel.name)
+    }
+    
+    /// Role names in a guild, keyed by role ID
+    async fn fetch_roles(&self, guild_id: &str) -> Result<HashMap<String, String>> {
+        let guild_id = guild_id.parse::<GuildId>()?;
+        let roles = self.http.get_guild_roles(guild_id).await?;
+        
+        Ok(roles
+            .into_iter()
+            .map(|role| (role.id.to_string(), role.name))
+            .collect())
+    }
+    
+    /// IDs of the roles a member holds in a guild
+    async fn fetch_member_roles(&self, guild_id: &str, user_id: &str) -> Result<Vec<String>> {
+        let guild_id = guild_id.parse::<GuildId>()?;
+        let user_id = user_id.parse::<UserId>()?;
+        let member = self.http.get_member(guild_id, user_id).await?;
+        
+        Ok(member.roles.iter().map(|id| id.to_string()).collect())
+    }
+    
+    async fn trigger_typing(&self, channel_id: &str) -> Result<()> {
+        let channel_id = channel_id.parse::<ChannelId>()?;
+        
+        channel_id.broadcast_typing(&self.http).await?;
+        
+        Ok(())
+    }
+    
+    async fn delete_message(&self, channel_id: &str, message_id: &str) -> Result<()> {
+        let channel_id = channel_id.parse::<ChannelId>()?;
+        let message_id = message_id.parse::<MessageId>()?;
+        
+        channel_id.delete_message(&self.http, message_id).await?;
+        
+        Ok(())
+    }
+    
+    async fn add_reaction(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        emoji: &ReactionEmoji,
+    ) -> Result<()> {
+        let channel_id = channel_id.parse::<ChannelId>()?;
+        let message_id = message_id.parse::<MessageId>()?;
+        
+        self.http.create_reaction(channel_id, message_id, &to_reaction_type(emoji)?).await?;
+        
+        Ok(())
+    }
+    
+    async fn remove_reaction(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        emoji: &ReactionEmoji,
+    ) -> Result<()> {
+        let channel_id = channel_id.parse::<ChannelId>()?;
+        let message_id = message_id.parse::<MessageId>()?;
+        
+        self.http.delete_reaction_me(channel_id, message_id, &to_reaction_type(emoji)?).await?;
+        
+        Ok(())
+    }
+    
+    /// One page of server-side search hits, newest first
+    async fn search_messages(
+        &self,
+        scope: &SearchScope,
+        query: &SearchQuery,
+        offset: usize,
+    ) -> Result<SearchPage> {
+        let path = match scope {
+            SearchScope::Guild(id) => format!("guilds/{}/messages/search", id),
+            SearchScope::Channel(id) => format!("channels/{}/messages/search", id),
+        };
+        
+        let response = self.rest
+            .get(format!("{}/api/v10/{}", self.api_base, path))
+            .header(reqwest::header::AUTHORIZATION, self.http.token())
+            .query(&query.params(offset))
+            .send()
+            .await?;
+        
+        // Discord answers 202 with no results while it is still indexing
+        if response.status() == reqwest::StatusCode::ACCEPTED {
+            anyhow::bail!("Discord is still indexing this server, try again in a moment");
         }
+        let response: SearchResponse = response.error_for_status()?.json().await?;
+        
+        Ok(SearchPage {
+            total_results: response.total_results,
+            messages: response.messages
+                .into_iter()
+                .filter_map(|hit| hit.into_iter().next())
+                .map(to_app_message)
+                .collect(),
+        })
+    }
+    
+    /// Pinned messages in a channel, most recently pinned first
+    async fn fetch_pins(&self, channel_id: &str) -> Result<Vec<AppMessage>> {
+        let channel_id = channel_id.parse::<ChannelId>()?;
+        let pins = self.http.get_pins(channel_id).await?;
+        
+        Ok(pins.into_iter().map(to_app_message).collect())
+    }
+    
+    async fn pin_message(&self, channel_id: &str, message_id: &str) -> Result<()> {
+        let channel_id = channel_id.parse::<ChannelId>()?;
+        let message_id = message_id.parse::<MessageId>()?;
+        
+        self.http.pin_message(channel_id, message_id, None).await?;
+        
+        Ok(())
+    }
+    
+    async fn unpin_message(&self, channel_id: &str, message_id: &str) -> Result<()> {
+        let channel_id = channel_id.parse::<ChannelId>()?;
+        let message_id = message_id.parse::<MessageId>()?;
         
-        let mut builder = CreateMessage::new();
+        self.http.unpin_message(channel_id, message_id, None).await?;
         
-        if !content.is_empty() {
-            builder = builder.content(content);
+        Ok(())
+    }
+    
+    /// Whether we may pin and unpin messages in a channel. Anyone can in a DM.
+    async fn can_manage_messages(&self, guild_id: Option<&str>, channel_id: &str, user_id: &str) -> Result<bool> {
+        let Some(guild_id) = guild_id else {
+            return Ok(true);
+        };
+        let guild_id = guild_id.parse::<GuildId>()?;
+        let channel_id = channel_id.parse::<ChannelId>()?;
+        let user_id = user_id.parse::<UserId>()?;
+        
+        let Channel::Guild(mut channel) = self.http.get_channel(channel_id).await? else {
+            return Ok(true);
+        };
+        // Threads have no overwrites of their own and follow their parent channel
+        if channel.thread_metadata.is_some() {
+            if let Some(parent_id) = channel.parent_id {
+                if let Channel::Guild(parent) = self.http.get_channel(parent_id).await? {
+                    channel = parent;
+                }
+            }
         }
         
-        for attachment in attachments {
-            builder = builder.add_file(attachment);
+        let guild = self.http.get_guild(guild_id).await?;
+        let member = self.http.get_member(guild_id, user_id).await?;
+        
+        Ok(guild.user_permissions_in(&channel, &member).manage_messages())
+    }
+}
+
+fn to_app_thread(channel: GuildChannel) -> Option<AppChannel> {
+    let archived = channel.thread_metadata.map(|m| m.archived).unwrap_or(false);
+    if archived {
+        return None;
+    }
+    
+    let parent_id = channel.parent_id?;
+    
+    Some(AppChannel::new(
+        channel.id.to_string(),
+        channel.name,
+        ChannelType::Thread,
+        0,
+    ).with_parent(parent_id.to_string()))
+}
+
+fn to_forum_post(thread: GuildChannel) -> ForumPost {
+    ForumPost::new(
+        thread.id.to_string(),
+        thread.parent_id.map(|p| p.to_string()).unwrap_or_default(),
+        thread.name,
+        thread.message_count.unwrap_or(0),
+    )
+    .with_tags(thread.applied_tags.iter().map(|t| t.to_string()).collect())
+    .with_last_message(thread.last_message_id.map(|id| id.to_string()))
+}
+
+fn to_app_emoji(reaction_type: ReactionType) -> Option<ReactionEmoji> {
+    match reaction_type {
+        ReactionType::Custom { animated, id, name } => Some(ReactionEmoji::custom(
+            id.to_string(),
+            name.unwrap_or_else(|| "emoji".to_string()),
+            animated,
+        )),
+        ReactionType::Unicode(name) => Some(ReactionEmoji::unicode(name)),
+        _ => None,
+    }
+}
+
+fn to_reaction_type(emoji: &ReactionEmoji) -> Result<ReactionType> {
+    Ok(match &emoji.id {
+        Some(id) => ReactionType::Custom {
+            animated: emoji.animated,
+            id: id.parse::<EmojiId>()?,
+            name: Some(emoji.name.clone()),
+        },
+        None => ReactionType::Unicode(emoji.name.clone()),
+    })
+}
+
+fn to_app_attachment(attachment: Attachment) -> AppAttachment {
+    AppAttachment::new(
+        attachment.id.to_string(),
+        attachment.filename,
+        attachment.url,
+        attachment.proxy_url,
+        attachment.width,
+        attachment.height,
+        attachment.content_type,
+    )
+}
+
+fn to_app_embed(embed: Embed) -> AppEmbed {
+    AppEmbed {
+        title: embed.title,
+        url: embed.url,
+        description: embed.description,
+        author: embed.author.map(|a| a.name),
+        provider: embed.provider.and_then(|p| p.name),
+        fields: embed.fields
+            .into_iter()
+            .map(|f| AppEmbedField::new(f.name, f.value, f.inline))
+            .collect(),
+        footer: embed.footer.map(|f| f.text),
+        color: embed.colour.map(|c| c.0),
+        thumbnail: embed.thumbnail
+            .map(|t| AppEmbedImage::new(t.proxy_url.unwrap_or(t.url))),
+        image: embed.image
+            .map(|i| AppEmbedImage::new(i.proxy_url.unwrap_or(i.url))),
+    }
+}
+
+fn to_app_sticker(item: StickerItem) -> Option<AppSticker> {
+    let format = match item.format_type {
+        StickerFormatType::Png => StickerFormat::Png,
+        StickerFormatType::Apng => StickerFormat::Apng,
+        StickerFormatType::Gif => StickerFormat::Gif,
+        StickerFormatType::Lottie => StickerFormat::Lottie,
+        _ => return None,
+    };
+    
+    Some(AppSticker::new(item.id.to_string(), item.name, format))
+}
+
+fn to_app_message(message: serenity::model::channel::Message) -> AppMessage {
+    let timestamp = message.timestamp.format("%H:%M:%S").to_string();
+    
+    let attachments: Vec<AppAttachment> = message.attachments
+        .into_iter()
+        .map(to_app_attachment)
+        .collect();
+    
+    let reactions: Vec<AppReaction> = message.reactions
+        .into_iter()
+        .filter_map(|r| {
+            let emoji = to_app_emoji(r.reaction_type)?;
+            Some(AppReaction::new(emoji, r.count, r.me))
+        })
+        .collect();
+    
+    let stickers: Vec<AppSticker> = message.sticker_items
+        .into_iter()
+        .filter_map(to_app_sticker)
+        .collect();
+    
+    let embeds: Vec<AppEmbed> = message.embeds
+        .into_iter()
+        .map(to_app_embed)
+        .filter(|e| !e.is_empty())
+        .collect();
+    
+    let mentioned_users: Vec<MentionedUser> = message.mentions
+        .iter()
+        .map(|u| MentionedUser::new(u.id.to_string(), u.display_name()))
+        .collect();
+    
+    let nonce = message.nonce.map(|nonce| match nonce {
+        Nonce::String(s) => s,
+        Nonce::Number(n) => n.to_string(),
+    });
+    
+    let reply_to = if message.kind == MessageType::InlineReply {
+        match message.referenced_message {
+            Some(referenced) => Some(AppReferencedMessage::new(
+                referenced.id.to_string(),
+                referenced.author.name,
+                referenced.author.id.to_string(),
+                referenced.content,
+            )),
+            None => message.message_reference
+                .and_then(|r| r.message_id)
+                .map(|id| AppReferencedMessage::unavailable(id.to_string())),
+        }
+    } else {
+        None
+    };
+    
+    AppMessage::new(
+        message.id.to_string(),
+        message.channel_id.to_string(),
+        message.author.name,
+        message.author.id.to_string(),
+        message.author.avatar.map(|h| h.to_string()),
+        message.content,
+        timestamp,
+    )
+    .with_attachments(attachments)
+    .with_stickers(stickers)
+    .with_embeds(embeds)
+    .with_edited(message.edited_timestamp.is_some())
+    .with_pinned(message.pinned)
+    .with_reply_to(reply_to)
+    .with_reactions(reactions)
+    .with_mentioned_users(mentioned_users)
+    .with_mention_roles(message.mention_roles.iter().map(|id| id.to_string()).collect())
+    .with_mentions_everyone(message.mention_everyone)
+    .with_guild_id(message.guild_id.map(|id| id.to_string()))
+    .with_nonce(nonce)
+}
+
+fn gateway_intents() -> GatewayIntents {
+    GatewayIntents::GUILDS 
+        | GatewayIntents::GUILD_MESSAGES 
+        | GatewayIntents::DIRECT_MESSAGES
+        | GatewayIntents::GUILD_MESSAGE_REACTIONS
+        | GatewayIntents::DIRECT_MESSAGE_REACTIONS
+        | GatewayIntents::GUILD_MESSAGE_TYPING
+        | GatewayIntents::DIRECT_MESSAGE_TYPING
+        | GatewayIntents::MESSAGE_CONTENT
+}
+
+/// Whether Discord answered that something does not exist (404) or is hidden from us
+/// (403), as opposed to a failure that may go away when tried again
+pub fn is_not_found(error: &anyhow::Error) -> bool {
+    use serenity::http::HttpError;
+    
+    match error.downcast_ref::<serenity::Error>() {
+        Some(serenity::Error::Http(HttpError::UnsuccessfulRequest(response))) => {
+            matches!(response.status_code.as_u16(), 403 | 404)
+        }
+        _ => false,
+    }
+}
+
+// `HttpBuilder::proxy`, `ClientBuilder::new_with_http` and the public `Client::ws_url`
+// come from the serenity fork pinned in Cargo.lock (gideonmt/serenity 30f5f3c0); check
+// them again when moving that pin.
+fn build_http(token: &str, api_base: &str) -> serenity::http::Http {
+    // Serenity sends everything meant for https://discord.com to the proxy instead
+    serenity::http::HttpBuilder::new(token)
+        .proxy(api_base)
+        .build()
+}
+
+async fn run_gateway(
+    token: String,
+    api_base: String,
+    gateway_url: String,
+    event_tx: mpsc::UnboundedSender<DiscordEvent>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let mut policy = ReconnectPolicy::default();
+    
+    loop {
+        if *shutdown.borrow() {
+            return;
         }
         
-        let message = channel_id.send_message(&self.http, builder).await?;
+        let _ = event_tx.send(DiscordEvent::ConnectionChanged(ConnectionState::Connecting));
+        let started = Instant::now();
         
-        let timestamp = message.timestamp.format("%H:%M:%S").to_string();
+        let handler = Handler { event_tx: event_tx.clone() };
+        let builder = serenity::client::ClientBuilder::new_with_http(build_http(&token, &api_base), gateway_intents());
+        let result = match builder.event_handler(handler).await {
+            Ok(mut client) => {
+                // Shards connect to whatever this holds, rather than asking the API. The fork
+                // exposes it as a public `Arc<Mutex<String>>`, see `build_http`.
+                *client.ws_url.lock().await = gateway_url.clone();
+                let shard_manager = client.shard_manager.clone();
+                tokio::select! {
+                    result = client.start() => result,
+                    _ = shutdown.changed() => {
+                        shard_manager.shutdown_all().await;
+                        return;
+                    }
+                }
+            }
+            Err(e) => Err(e),
+        };
         
-        let attachments: Vec<AppAttachment> = message.attachments
-            .into_iter()
-            .map(|a| AppAttachment::new(
-                a.id.to_string(),
-                a.filename,
-                a.url,
-                a.proxy_url,
-                a.width,
-                a.height,
-                a.content_type,
-            ))
-            .collect();
+        if event_tx.is_closed() {
+            return;
+        }
         
-        Ok(AppMessage::new(
-            message.id.to_string(),
-            channel_id.to_string(),
-            message.author.name,
-            message.author.id.to_string(),
-            message.author.avatar.map(|h| h.to_string()),
-            message.content,
-            timestamp,
-        ).with_attachments(attachments))
+        let reason = match result {
+            Ok(()) => "Gateway connection closed".to_string(),
+            Err(e) if is_fatal(&e) => {
+                let _ = event_tx.send(DiscordEvent::ConnectionChanged(
+                    ConnectionState::Disconnected(e.to_string())
+                ));
+                return;
+            }
+            Err(e) => e.to_string(),
+        };
+        
+        if started.elapsed() >= STABLE_CONNECTION {
+            policy.reset();
+        }
+        let delay = policy.next_delay();
+        let _ = event_tx.send(DiscordEvent::ConnectionChanged(ConnectionState::Retrying {
+            attempt: policy.attempt(),
+            at: Instant::now() + delay,
+            reason,
+        }));
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.changed() => return,
+        }
     }
 }
 
+/// Errors that will fail the same way however often we reconnect
+fn is_fatal(error: &serenity::Error) -> bool {
+    use serenity::gateway::GatewayError;
+    
+    matches!(
+        error,
+        serenity::Error::Gateway(
+            GatewayError::InvalidAuthentication
+                | GatewayError::InvalidGatewayIntents
+                | GatewayError::DisallowedGatewayIntents
+                | GatewayError::InvalidShardData
+                | GatewayError::OverloadedShard
+        )
+    )
+}
+
 struct Handler {
     event_tx: mpsc::UnboundedSender<DiscordEvent>,
 }
@@ -298,6 +943,8 @@ struct Handler {
 #[serenity::async_trait]
 impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, ready: Ready) {
+        let _ = self.event_tx.send(DiscordEvent::ConnectionChanged(ConnectionState::Ready));
+        
         let guilds: Vec<AppGuild> = ready.guilds
             .iter()
             .map(|g| AppGuild::new(g.id.to_string(), g.id.to_string()))
@@ -307,41 +954,191 @@ impl EventHandler for Handler {
         
         for guild in ready.guilds {
             if let Ok(full_guild) = ctx.http.get_guild(guild.id).await {
-                let app_guild = AppGuild::new(full_guild.id.to_string(), full_guild.name);
+                let app_guild = AppGuild::new(full_guild.id.to_string(), full_guild.name)
+                    .with_icon(full_guild.icon.map(|h| h.to_string()));
                 let _ = self.event_tx.send(DiscordEvent::Ready(vec![app_guild]));
             }
         }
         
+        let user_id = ready.user.id.to_string();
         let username = ready.user.name.clone();
-        let _ = self.event_tx.send(DiscordEvent::Connected(username));
+        let _ = self.event_tx.send(DiscordEvent::Connected(user_id, username));
+    }
+    
+    async fn resume(&self, _ctx: Context, _event: ResumedEvent) {
+        let _ = self.event_tx.send(DiscordEvent::ConnectionChanged(ConnectionState::Ready));
+    }
+    
+    async fn shard_stage_update(&self, _ctx: Context, event: serenity::gateway::ShardStageUpdateEvent) {
+        use serenity::gateway::ConnectionStage;
+        
+        let state = match event.new {
+            ConnectionStage::Resuming => ConnectionState::Resuming,
+            ConnectionStage::Disconnected => ConnectionState::Disconnected("Connection lost".to_string()),
+            ConnectionStage::Connected => return,
+            _ => ConnectionState::Connecting,
+        };
+        let _ = self.event_tx.send(DiscordEvent::ConnectionChanged(state));
     }
     
     async fn message(&self, _ctx: Context, new_message: serenity::model::channel::Message) {
-        let timestamp = new_message.timestamp.format("%H:%M:%S").to_string();
+        let _ = self.event_tx.send(DiscordEvent::NewMessage(to_app_message(new_message)));
+    }
+    
+    async fn typing_start(&self, ctx: Context, event: TypingStartEvent) {
+        let name = match &event.member {
+            Some(member) => Some(member.display_name().to_string()),
+            None => ctx.cache.user(event.user_id).map(|u| u.display_name().to_string()),
+        };
         
-        let attachments: Vec<AppAttachment> = new_message.attachments
-            .into_iter()
-            .map(|a| AppAttachment::new(
-                a.id.to_string(),
-                a.filename,
-                a.url,
-                a.proxy_url,
-                a.width,
-                a.height,
-                a.content_type,
-            ))
-            .collect();
+        let _ = self.event_tx.send(DiscordEvent::TypingStarted(
+            event.channel_id.to_string(),
+            event.user_id.to_string(),
+            name,
+        ));
+    }
+    
+    async fn message_update(
+        &self,
+        _ctx: Context,
+        _old_if_available: Option<serenity::model::channel::Message>,
+        _new: Option<serenity::model::channel::Message>,
+        event: MessageUpdateEvent,
+    ) {
+        if let Some(content) = event.content {
+            let _ = self.event_tx.send(DiscordEvent::MessageEdited(
+                event.channel_id.to_string(),
+                event.id.to_string(),
+                content,
+            ));
+        }
+        
+        // Link previews usually arrive as an update shortly after the message
+        if let Some(embeds) = event.embeds {
+            let embeds = embeds
+                .into_iter()
+                .map(to_app_embed)
+                .filter(|e| !e.is_empty())
+                .collect();
+            let _ = self.event_tx.send(DiscordEvent::EmbedsUpdated(
+                event.channel_id.to_string(),
+                event.id.to_string(),
+                embeds,
+            ));
+        }
+    }
+    
+    async fn message_delete(
+        &self,
+        _ctx: Context,
+        channel_id: ChannelId,
+        deleted_message_id: MessageId,
+        _guild_id: Option<GuildId>,
+    ) {
+        let _ = self.event_tx.send(DiscordEvent::MessageDeleted(
+            channel_id.to_string(),
+            deleted_message_id.to_string(),
+        ));
+    }
+    
+    async fn message_delete_bulk(
+        &self,
+        _ctx: Context,
+        channel_id: ChannelId,
+        multiple_deleted_messages_ids: Vec<MessageId>,
+        _guild_id: Option<GuildId>,
+    ) {
+        for message_id in multiple_deleted_messages_ids {
+            let _ = self.event_tx.send(DiscordEvent::MessageDeleted(
+                channel_id.to_string(),
+                message_id.to_string(),
+            ));
+        }
+    }
+    
+    async fn channel_pins_update(&self, _ctx: Context, pin: ChannelPinsUpdateEvent) {
+        let _ = self.event_tx.send(DiscordEvent::PinsUpdated(pin.channel_id.to_string()));
+    }
+    
+    async fn thread_create(&self, _ctx: Context, thread: GuildChannel) {
+        let guild_id = thread.guild_id.to_string();
+        if let Some(thread) = to_app_thread(thread) {
+            let _ = self.event_tx.send(DiscordEvent::ThreadUpdated(guild_id, thread));
+        }
+    }
+    
+    async fn thread_update(&self, _ctx: Context, _old: Option<GuildChannel>, new: GuildChannel) {
+        let guild_id = new.guild_id.to_string();
+        let thread_id = new.id.to_string();
+        
+        // Archived threads drop out of the sidebar like deleted ones
+        let event = match to_app_thread(new) {
+            Some(thread) => DiscordEvent::ThreadUpdated(guild_id, thread),
+            None => DiscordEvent::ThreadDeleted(guild_id, thread_id),
+        };
+        let _ = self.event_tx.send(event);
+    }
+    
+    async fn thread_delete(
+        &self,
+        _ctx: Context,
+        thread: PartialGuildChannel,
+        _full_thread_data: Option<GuildChannel>,
+    ) {
+        let _ = self.event_tx.send(DiscordEvent::ThreadDeleted(
+            thread.guild_id.to_string(),
+            thread.id.to_string(),
+        ));
+    }
+    
+    async fn reaction_add(&self, _ctx: Context, add_reaction: Reaction) {
+        let (Some(user_id), Some(emoji)) = (add_reaction.user_id, to_app_emoji(add_reaction.emoji)) else {
+            return;
+        };
+        
+        let _ = self.event_tx.send(DiscordEvent::ReactionAdded(
+            add_reaction.channel_id.to_string(),
+            add_reaction.message_id.to_string(),
+            emoji,
+            user_id.to_string(),
+        ));
+    }
+    
+    async fn reaction_remove(&self, _ctx: Context, removed_reaction: Reaction) {
+        let (Some(user_id), Some(emoji)) = (removed_reaction.user_id, to_app_emoji(removed_reaction.emoji)) else {
+            return;
+        };
+        
+        let _ = self.event_tx.send(DiscordEvent::ReactionRemoved(
+            removed_reaction.channel_id.to_string(),
+            removed_reaction.message_id.to_string(),
+            emoji,
+            user_id.to_string(),
+        ));
+    }
+    
+    async fn reaction_remove_all(
+        &self,
+        _ctx: Context,
+        channel_id: ChannelId,
+        removed_from_message_id: MessageId,
+    ) {
+        let _ = self.event_tx.send(DiscordEvent::ReactionsCleared(
+            channel_id.to_string(),
+            removed_from_message_id.to_string(),
+            None,
+        ));
+    }
+    
+    async fn reaction_remove_emoji(&self, _ctx: Context, removed_reactions: Reaction) {
+        let Some(emoji) = to_app_emoji(removed_reactions.emoji) else {
+            return;
+        };
         
-        let app_message = AppMessage::new(
-            new_message.id.to_string(),
-            new_message.channel_id.to_string(),
-            new_message.author.name,
-            new_message.author.id.to_string(),
-            new_message.author.avatar.map(|h| h.to_string()),
-            new_message.content,
-            timestamp,
-        ).with_attachments(attachments);
-        
-        let _ = self.event_tx.send(DiscordEvent::NewMessage(app_message));
+        let _ = self.event_tx.send(DiscordEvent::ReactionsCleared(
+            removed_reactions.channel_id.to_string(),
+            removed_reactions.message_id.to_string(),
+            Some(emoji),
+        ));
     }
 }
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use serenity::async_trait;
+use std::collections::HashMap;
+
+use crate::models::{
+    ChannelList, DmChannel, ForumPost, Message, OutgoingMessage, ReactionEmoji, SearchPage,
+    SearchQuery, SearchScope,
+};
+
+/// A source of chat data the app can talk to: Discord itself or a scripted stand-in.
+///
+/// Each backend's constructor also hands back the receiving end of its `DiscordEvent`
+/// stream. Nothing is sent on it until `start_gateway` is called.
+#[async_trait]
+pub trait ChatBackend: Send + Sync {
+    /// Whether the backend answers at all, checked once at startup
+    async fn is_reachable(&self) -> bool;
+
+    /// Start delivering live events, reconnecting as needed
+    async fn start_gateway(&self) -> Result<()>;
+
+    /// Close the gateway for good, for when we switch to another account
+    async fn shutdown(&self);
+
+    async fn fetch_dms(&self) -> Result<Vec<DmChannel>>;
+
+    async fn fetch_channels(&self, guild_id: &str) -> Result<ChannelList>;
+
+    /// Active and recently archived posts in a forum, most recently active first
+    async fn fetch_forum_posts(&self, guild_id: &str, forum_id: &str) -> Result<Vec<ForumPost>>;
+
+    async fn create_forum_post(
+        &self,
+        forum_id: &str,
+        title: &str,
+        content: &str,
+        tag_ids: &[String],
+    ) -> Result<ForumPost>;
+
+    /// Fetch up to `limit` messages, oldest first, optionally only those older than `before`
+    async fn fetch_messages(
+        &self,
+        channel_id: &str,
+        limit: u8,
+        before: Option<&str>,
+    ) -> Result<Vec<Message>>;
+
+    /// Messages sent after `after`, oldest first
+    async fn fetch_messages_after(
+        &self,
+        channel_id: &str,
+        limit: u8,
+        after: &str,
+    ) -> Result<Vec<Message>>;
+
+    /// Send an outbox message in the background. The result comes back as
+    /// `MessageSent` or `SendFailed` on the event channel.
+    fn spawn_send(&self, outgoing: OutgoingMessage);
+
+    async fn edit_message(&self, channel_id: &str, message_id: &str, content: &str) -> Result<Message>;
+
+    async fn delete_message(&self, channel_id: &str, message_id: &str) -> Result<()>;
+
+    async fn fetch_user_name(&self, user_id: &str) -> Result<String>;
+
+    async fn fetch_channel_name(&self, channel_id: &str) -> Result<String>;
+
+    /// Role names in a guild, keyed by role ID
+    async fn fetch_roles(&self, guild_id: &str) -> Result<HashMap<String, String>>;
+
+    /// IDs of the roles a member holds in a guild
+    async fn fetch_member_roles(&self, guild_id: &str, user_id: &str) -> Result<Vec<String>>;
+
+    async fn trigger_typing(&self, channel_id: &str) -> Result<()>;
+
+    async fn add_reaction(&self, channel_id: &str, message_id: &str, emoji: &ReactionEmoji) -> Result<()>;
+
+    async fn remove_reaction(&self, channel_id: &str, message_id: &str, emoji: &ReactionEmoji) -> Result<()>;
+
+    /// One page of search hits, newest first
+    async fn search_messages(
+        &self,
+        scope: &SearchScope,
+        query: &SearchQuery,
+        offset: usize,
+    ) -> Result<SearchPage>;
+
+    /// Pinned messages in a channel, most recently pinned first
+    async fn fetch_pins(&self, channel_id: &str) -> Result<Vec<Message>>;
+
+    async fn pin_message(&self, channel_id: &str, message_id: &str) -> Result<()>;
+
+    async fn unpin_message(&self, channel_id: &str, message_id: &str) -> Result<()>;
+
+    /// Whether `user_id`, which is us, may pin and unpin messages in a channel
+    async fn can_manage_messages(&self, guild_id: Option<&str>, channel_id: &str, user_id: &str) -> Result<bool>;
+}
@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+
+/// Delay before the first reconnect attempt, doubled on each failure
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// A connection that lasted this long counts as healthy and resets the backoff
+pub const STABLE_CONNECTION: Duration = Duration::from_secs(60);
+
+/// State of the gateway connection, as shown in the sidebar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Ready,
+    Resuming,
+    Disconnected(String),
+    Retrying { attempt: u32, at: Instant, reason: String },
+}
+
+impl ConnectionState {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, ConnectionState::Ready)
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            ConnectionState::Connecting => "Connecting...".to_string(),
+            ConnectionState::Ready => "Connected".to_string(),
+            ConnectionState::Resuming => "Resuming...".to_string(),
+            ConnectionState::Disconnected(reason) => format!("Disconnected: {}", reason),
+            ConnectionState::Retrying { attempt, at, .. } => {
+                let secs = at.saturating_duration_since(Instant::now()).as_secs_f32().ceil() as u64;
+                format!("Retrying in {}s (attempt {})", secs, attempt)
+            }
+        }
+    }
+}
+
+/// Exponential backoff between gateway restarts
+#[derive(Debug, Default)]
+pub struct ReconnectPolicy {
+    attempt: u32,
+}
+
+impl ReconnectPolicy {
+    /// Record a failed connection and return how long to wait before the next one
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = INITIAL_RETRY_DELAY
+            .saturating_mul(1 << self.attempt.min(6))
+            .min(MAX_RETRY_DELAY);
+        self.attempt += 1;
+        delay
+    }
+
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
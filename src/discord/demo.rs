@@ -0,0 +1,749 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serenity::async_trait;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::api::DiscordEvent;
+use super::backend::ChatBackend;
+use super::connection::ConnectionState;
+
+use crate::models::mention::{find_mentions, Mention};
+use crate::models::snowflake;
+use crate::models::{
+    Channel, ChannelCategory, ChannelList, ChannelType, DmChannel, DmUser, ForumPost, ForumTag,
+    Guild, MentionedUser, Message, OutgoingMessage, Reaction, ReactionEmoji, ReferencedMessage,
+    SearchPage, SearchQuery, SearchScope, SEARCH_PAGE_SIZE,
+};
+
+/// Served when `--demo` is given without a fixture file
+const DEFAULT_FIXTURE: &str = include_str!("../../fixtures/demo.yaml");
+
+// How long a scripted author is shown typing before their message arrives
+const SCRIPTED_TYPING: Duration = Duration::from_secs(3);
+
+// Spacing of fixture messages, the newest of each channel being sent at load time
+const FIXTURE_MESSAGE_SPACING_MS: i64 = 60_000;
+
+#[derive(Deserialize)]
+struct Fixture {
+    user: FixtureUser,
+    #[serde(default)]
+    users: HashMap<String, String>,
+    #[serde(default)]
+    guilds: Vec<FixtureGuild>,
+    #[serde(default)]
+    dms: Vec<FixtureDm>,
+    /// Keyed by channel ID, oldest first
+    #[serde(default)]
+    messages: HashMap<String, Vec<FixtureMessage>>,
+    #[serde(default)]
+    script: Vec<ScriptedMessage>,
+}
+
+#[derive(Deserialize)]
+struct FixtureUser {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct FixtureGuild {
+    id: String,
+    name: String,
+    #[serde(default)]
+    roles: HashMap<String, String>,
+    /// Roles the demo user holds
+    #[serde(default)]
+    member_roles: Vec<String>,
+    #[serde(default)]
+    categories: Vec<FixtureCategory>,
+    #[serde(default)]
+    channels: Vec<FixtureChannel>,
+}
+
+#[derive(Deserialize)]
+struct FixtureCategory {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct FixtureChannel {
+    id: String,
+    name: String,
+    #[serde(default = "default_channel_kind")]
+    kind: ChannelType,
+    category: Option<String>,
+    /// Parent channel of a thread or forum post
+    parent: Option<String>,
+    /// Tags that can be applied to posts, for a forum
+    #[serde(default)]
+    available_tags: Vec<ForumTag>,
+    /// Tag IDs applied to a forum post
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct FixtureDm {
+    id: String,
+    user: String,
+}
+
+#[derive(Deserialize)]
+struct FixtureMessage {
+    /// Only a name for `reply_to`; the real ID is made at load time
+    id: String,
+    author: String,
+    content: String,
+    reply_to: Option<String>,
+    #[serde(default)]
+    reactions: Vec<FixtureReaction>,
+    #[serde(default)]
+    pinned: bool,
+    #[serde(default)]
+    edited: bool,
+}
+
+#[derive(Deserialize)]
+struct FixtureReaction {
+    emoji: String,
+    count: u64,
+    #[serde(default)]
+    me: bool,
+}
+
+/// A message sent over the scripted gateway once the demo is running
+#[derive(Deserialize, Clone)]
+struct ScriptedMessage {
+    /// Seconds after the previous scripted message
+    after_secs: u64,
+    channel: String,
+    author: String,
+    content: String,
+    #[serde(default)]
+    typing: bool,
+}
+
+fn default_channel_kind() -> ChannelType {
+    ChannelType::Text
+}
+
+/// What changes while the demo runs
+struct DemoState {
+    /// Keyed by guild ID
+    channels: HashMap<String, ChannelList>,
+    /// Keyed by channel ID, oldest first
+    messages: HashMap<String, Vec<Message>>,
+    /// Tag IDs applied to each forum post
+    post_tags: HashMap<String, Vec<String>>,
+}
+
+/// Serves guilds, channels and messages from a fixture file, with no token or network.
+/// Changes made while running are kept in memory only.
+#[derive(Clone)]
+pub struct DemoBackend {
+    fixture: Arc<Fixture>,
+    state: Arc<Mutex<DemoState>>,
+    event_tx: mpsc::UnboundedSender<DiscordEvent>,
+}
+
+impl DemoBackend {
+    /// Load a fixture file, or the bundled one when `path` is `None`
+    pub fn new(path: Option<&Path>) -> Result<(Self, mpsc::UnboundedReceiver<DiscordEvent>)> {
+        let source = match path {
+            Some(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("Could not read demo fixture {}", path.display()))?,
+            None => DEFAULT_FIXTURE.to_string(),
+        };
+        let fixture: Fixture = serde_yaml::from_str(&source).context("Demo fixture is invalid")?;
+
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let backend = Self {
+            fixture: Arc::new(fixture),
+            state: Arc::new(Mutex::new(DemoState {
+                channels: HashMap::new(),
+                messages: HashMap::new(),
+                post_tags: HashMap::new(),
+            })),
+            event_tx,
+        };
+        backend.load_fixture();
+
+        Ok((backend, event_rx))
+    }
+
+    fn load_fixture(&self) {
+        let mut channels = HashMap::new();
+        let mut post_tags = HashMap::new();
+        for guild in &self.fixture.guilds {
+            let mut list = ChannelList::new();
+            for (position, category) in guild.categories.iter().enumerate() {
+                list.categories.push(ChannelCategory::new(&category.id, &category.name, position as i32));
+            }
+            for (position, channel) in guild.channels.iter().enumerate() {
+                let mut app_channel = Channel::new(&channel.id, &channel.name, channel.kind, position as i32)
+                    .with_tags(channel.available_tags.clone());
+                if let Some(parent) = channel.parent.as_ref().or(channel.category.as_ref()) {
+                    app_channel = app_channel.with_parent(parent);
+                }
+                if channel.kind == ChannelType::Thread {
+                    post_tags.insert(channel.id.clone(), channel.tags.clone());
+                    list.threads.push(app_channel);
+                } else {
+                    list.channels.push(app_channel);
+                }
+            }
+            list.sort();
+            channels.insert(guild.id.clone(), list);
+        }
+        {
+            let mut state = self.state();
+            state.channels = channels;
+            state.post_tags = post_tags;
+        }
+
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut sequence = 0;
+        let mut messages = HashMap::new();
+        for (channel_id, fixture_messages) in &self.fixture.messages {
+            let mut ids: HashMap<&str, String> = HashMap::new();
+            let mut loaded: Vec<Message> = Vec::new();
+            let count = fixture_messages.len() as i64;
+
+            for (i, fixture_message) in fixture_messages.iter().enumerate() {
+                let sent_ms = now_ms - (count - 1 - i as i64) * FIXTURE_MESSAGE_SPACING_MS;
+                // Low bits keep messages of different channels sent at the same time distinct
+                sequence += 1;
+                let id = (snowflake::from_timestamp_ms(sent_ms).parse::<u64>().unwrap_or(0) + sequence).to_string();
+                ids.insert(&fixture_message.id, id.clone());
+
+                let reply_to = fixture_message.reply_to.as_deref().map(|name| {
+                    ids.get(name)
+                        .and_then(|id| loaded.iter().find(|m| &m.id == id))
+                        .map(to_referenced)
+                        .unwrap_or_else(|| ReferencedMessage::unavailable(name))
+                });
+                let reactions = fixture_message.reactions
+                    .iter()
+                    .filter_map(|r| Some(Reaction::new(ReactionEmoji::parse(&r.emoji)?, r.count, r.me)))
+                    .collect();
+
+                loaded.push(
+                    self.build_message(channel_id, &id, &fixture_message.author, &fixture_message.content)
+                        .with_reply_to(reply_to)
+                        .with_reactions(reactions)
+                        .with_pinned(fixture_message.pinned)
+                        .with_edited(fixture_message.edited),
+                );
+            }
+            messages.insert(channel_id.clone(), loaded);
+        }
+
+        self.state().messages = messages;
+    }
+
+    fn state(&self) -> MutexGuard<'_, DemoState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn user_name(&self, user_id: &str) -> String {
+        if user_id == self.fixture.user.id {
+            return self.fixture.user.name.clone();
+        }
+        self.fixture.users.get(user_id).cloned().unwrap_or_else(|| user_id.to_string())
+    }
+
+    fn guild_of(&self, channel_id: &str) -> Option<String> {
+        self.state()
+            .channels
+            .iter()
+            .find(|(_, list)| list.find(channel_id).is_some())
+            .map(|(guild_id, _)| guild_id.clone())
+    }
+
+    /// A message as Discord would hand it to us, with mentions resolved
+    fn build_message(&self, channel_id: &str, id: &str, author_id: &str, content: &str) -> Message {
+        let mentioned_users = find_mentions(content)
+            .filter_map(|mention| match mention {
+                Mention::User(user_id) => Some(MentionedUser::new(&user_id, self.user_name(&user_id))),
+                _ => None,
+            })
+            .collect();
+        let mention_roles = find_mentions(content)
+            .filter_map(|mention| match mention {
+                Mention::Role(role_id) => Some(role_id),
+                _ => None,
+            })
+            .collect();
+        let timestamp = snowflake::timestamp_ms(id)
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|time| time.format("%H:%M:%S").to_string())
+            .unwrap_or_default();
+
+        Message::new(id, channel_id, self.user_name(author_id), author_id, None, content, timestamp)
+            .with_guild_id(self.guild_of(channel_id))
+            .with_mentioned_users(mentioned_users)
+            .with_mention_roles(mention_roles)
+            .with_mentions_everyone(content.contains("@everyone"))
+    }
+
+    fn update_message<T>(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        f: impl FnOnce(&mut Message) -> T,
+    ) -> Result<T> {
+        let mut state = self.state();
+        let message = state.messages
+            .get_mut(channel_id)
+            .and_then(|messages| messages.iter_mut().find(|m| m.id == message_id))
+            .with_context(|| format!("Unknown message {}", message_id))?;
+        Ok(f(message))
+    }
+
+    /// Deliver a scripted message once its delay is up, optionally typing first
+    async fn play(&self, scripted: ScriptedMessage) {
+        tokio::time::sleep(Duration::from_secs(scripted.after_secs)).await;
+        if scripted.typing {
+            let name = Some(self.user_name(&scripted.author));
+            let _ = self.event_tx.send(DiscordEvent::TypingStarted(
+                scripted.channel.clone(),
+                scripted.author.clone(),
+                name,
+            ));
+            tokio::time::sleep(SCRIPTED_TYPING).await;
+        }
+
+        let message = self.build_message(&scripted.channel, &snowflake::generate(), &scripted.author, &scripted.content);
+        self.state().messages.entry(scripted.channel).or_default().push(message.clone());
+        let _ = self.event_tx.send(DiscordEvent::NewMessage(message));
+    }
+}
+
+#[async_trait]
+impl ChatBackend for DemoBackend {
+    async fn is_reachable(&self) -> bool {
+        true
+    }
+
+    /// Connect straight away, then play the fixture's script in the background
+    async fn start_gateway(&self) -> Result<()> {
+        let guilds = self.fixture.guilds
+            .iter()
+            .map(|g| Guild::new(&g.id, &g.name))
+            .collect();
+
+        let _ = self.event_tx.send(DiscordEvent::ConnectionChanged(ConnectionState::Ready));
+        let _ = self.event_tx.send(DiscordEvent::Ready(guilds));
+        let _ = self.event_tx.send(DiscordEvent::Connected(
+            self.fixture.user.id.clone(),
+            self.fixture.user.name.clone(),
+        ));
+
+        let backend = self.clone();
+        tokio::spawn(async move {
+            for scripted in backend.fixture.script.clone() {
+                backend.play(scripted).await;
+            }
+        });
+        Ok(())
+    }
+
+    async fn shutdown(&self) {}
+
+    async fn fetch_dms(&self) -> Result<Vec<DmChannel>> {
+        Ok(self.fixture.dms
+            .iter()
+            .map(|dm| DmChannel::new(&dm.id, DmUser::new(&dm.user, self.user_name(&dm.user), "0")))
+            .collect())
+    }
+
+    async fn fetch_channels(&self, guild_id: &str) -> Result<ChannelList> {
+        self.state()
+            .channels
+            .get(guild_id)
+            .cloned()
+            .with_context(|| format!("Unknown guild {}", guild_id))
+    }
+
+    async fn fetch_forum_posts(&self, guild_id: &str, forum_id: &str) -> Result<Vec<ForumPost>> {
+        let state = self.state();
+        let list = state.channels
+            .get(guild_id)
+            .with_context(|| format!("Unknown guild {}", guild_id))?;
+
+        let mut posts: Vec<ForumPost> = list
+            .threads_in(forum_id)
+            .into_iter()
+            .map(|thread| {
+                let messages = state.messages.get(&thread.id);
+                ForumPost::new(
+                    &thread.id,
+                    forum_id,
+                    &thread.name,
+                    messages.map_or(0, |m| m.len() as u32),
+                )
+                .with_tags(state.post_tags.get(&thread.id).cloned().unwrap_or_default())
+                .with_last_message(messages.and_then(|m| m.last()).map(|m| m.id.clone()))
+            })
+            .collect();
+        posts.sort_by_key(|p| std::cmp::Reverse(p.last_activity_ms()));
+
+        Ok(posts)
+    }
+
+    async fn create_forum_post(
+        &self,
+        forum_id: &str,
+        title: &str,
+        content: &str,
+        tag_ids: &[String],
+    ) -> Result<ForumPost> {
+        let guild_id = self.guild_of(forum_id).with_context(|| format!("Unknown forum {}", forum_id))?;
+        let thread = Channel::new(snowflake::generate(), title, ChannelType::Thread, 0).with_parent(forum_id);
+        let message = self.build_message(&thread.id, &snowflake::generate(), &self.fixture.user.id, content);
+
+        let mut state = self.state();
+        if let Some(list) = state.channels.get_mut(&guild_id) {
+            list.upsert_thread(thread.clone());
+        }
+        state.post_tags.insert(thread.id.clone(), tag_ids.to_vec());
+        state.messages.insert(thread.id.clone(), vec![message.clone()]);
+        drop(state);
+
+        let _ = self.event_tx.send(DiscordEvent::ThreadUpdated(guild_id, thread.clone()));
+
+        Ok(ForumPost::new(&thread.id, forum_id, title, 1)
+            .with_tags(tag_ids.to_vec())
+            .with_last_message(Some(message.id)))
+    }
+
+    async fn fetch_messages(
+        &self,
+        channel_id: &str,
+        limit: u8,
+        before: Option<&str>,
+    ) -> Result<Vec<Message>> {
+        let before = before.map(snowflake::value);
+        let mut messages: Vec<Message> = self.state()
+            .messages
+            .get(channel_id)
+            .into_iter()
+            .flatten()
+            .filter(|m| before.is_none_or(|before| snowflake::value(&m.id) < before))
+            .cloned()
+            .collect();
+        messages.drain(..messages.len().saturating_sub(limit as usize));
+
+        Ok(messages)
+    }
+
+    async fn fetch_messages_after(
+        &self,
+        channel_id: &str,
+        limit: u8,
+        after: &str,
+    ) -> Result<Vec<Message>> {
+        let after = snowflake::value(after);
+        Ok(self.state()
+            .messages
+            .get(channel_id)
+            .into_iter()
+            .flatten()
+            .filter(|m| snowflake::value(&m.id) > after)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    /// Files are not uploaded anywhere, so messages with attachments fail
+    fn spawn_send(&self, outgoing: OutgoingMessage) {
+        if !outgoing.file_paths.is_empty() {
+            let _ = self.event_tx.send(DiscordEvent::SendFailed(
+                outgoing.channel_id,
+                outgoing.nonce,
+                "Attachments can't be sent in the demo".to_string(),
+            ));
+            return;
+        }
+
+        let reply_to = outgoing.reply_to.as_ref().map(|reply| {
+            self.state()
+                .messages
+                .get(&outgoing.channel_id)
+                .and_then(|messages| messages.iter().find(|m| m.id == reply.message_id))
+                .map(to_referenced)
+                .unwrap_or_else(|| ReferencedMessage::unavailable(&reply.message_id))
+        });
+        let message = self
+            .build_message(&outgoing.channel_id, &snowflake::generate(), &self.fixture.user.id, &outgoing.content)
+            .with_reply_to(reply_to)
+            .with_nonce(Some(outgoing.nonce.clone()));
+
+        self.state().messages.entry(outgoing.channel_id).or_default().push(message.clone());
+        let _ = self.event_tx.send(DiscordEvent::MessageSent(outgoing.nonce, message));
+    }
+
+    async fn edit_message(&self, channel_id: &str, message_id: &str, content: &str) -> Result<Message> {
+        let message = self.update_message(channel_id, message_id, |message| {
+            message.content = content.to_string();
+            message.edited = true;
+            message.clone()
+        })?;
+        let _ = self.event_tx.send(DiscordEvent::MessageEdited(
+            channel_id.to_string(),
+            message_id.to_string(),
+            content.to_string(),
+        ));
+        Ok(message)
+    }
+
+    async fn delete_message(&self, channel_id: &str, message_id: &str) -> Result<()> {
+        if let Some(messages) = self.state().messages.get_mut(channel_id) {
+            messages.retain(|m| m.id != message_id);
+        }
+        let _ = self.event_tx.send(DiscordEvent::MessageDeleted(channel_id.to_string(), message_id.to_string()));
+        Ok(())
+    }
+
+    async fn fetch_user_name(&self, user_id: &str) -> Result<String> {
+        if user_id != self.fixture.user.id && !self.fixture.users.contains_key(user_id) {
+            anyhow::bail!("Unknown user {}", user_id);
+        }
+        Ok(self.user_name(user_id))
+    }
+
+    async fn fetch_channel_name(&self, channel_id: &str) -> Result<String> {
+        self.state()
+            .channels
+            .values()
+            .find_map(|list| list.find(channel_id))
+            .map(|channel| channel.name.clone())
+            .with_context(|| format!("Unknown channel {}", channel_id))
+    }
+
+    async fn fetch_roles(&self, guild_id: &str) -> Result<HashMap<String, String>> {
+        self.fixture.guilds
+            .iter()
+            .find(|g| g.id == guild_id)
+            .map(|g| g.roles.clone())
+            .with_context(|| format!("Unknown guild {}", guild_id))
+    }
+
+    async fn fetch_member_roles(&self, guild_id: &str, user_id: &str) -> Result<Vec<String>> {
+        let guild = self.fixture.guilds
+            .iter()
+            .find(|g| g.id == guild_id)
+            .with_context(|| format!("Unknown guild {}", guild_id))?;
+        if user_id != self.fixture.user.id {
+            return Ok(Vec::new());
+        }
+        Ok(guild.member_roles.clone())
+    }
+
+    async fn trigger_typing(&self, _channel_id: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn add_reaction(&self, channel_id: &str, message_id: &str, emoji: &ReactionEmoji) -> Result<()> {
+        self.update_message(channel_id, message_id, |message| message.add_reaction(emoji.clone(), true))
+    }
+
+    async fn remove_reaction(&self, channel_id: &str, message_id: &str, emoji: &ReactionEmoji) -> Result<()> {
+        self.update_message(channel_id, message_id, |message| message.remove_reaction(emoji, true))
+    }
+
+    /// Matches content, authors, channels and ID ranges. `has:` filters are ignored.
+    async fn search_messages(
+        &self,
+        scope: &SearchScope,
+        query: &SearchQuery,
+        offset: usize,
+    ) -> Result<SearchPage> {
+        let in_scope: Vec<String> = match scope {
+            SearchScope::Channel(id) => vec![id.clone()],
+            SearchScope::Guild(id) => self.state()
+                .channels
+                .get(id)
+                .map(|list| list.channels.iter().chain(&list.threads).map(|c| c.id.clone()).collect())
+                .unwrap_or_default(),
+        };
+        let content = query.content.to_lowercase();
+        let min_id = query.min_id.as_deref().map(snowflake::value);
+        let max_id = query.max_id.as_deref().map(snowflake::value);
+
+        let state = self.state();
+        let mut hits: Vec<Message> = in_scope
+            .iter()
+            .filter(|id| query.channel_ids.is_empty() || query.channel_ids.contains(*id))
+            .filter_map(|id| state.messages.get(id))
+            .flatten()
+            .filter(|m| m.content.to_lowercase().contains(&content))
+            .filter(|m| query.author_ids.is_empty() || query.author_ids.contains(&m.author_id))
+            .filter(|m| min_id.is_none_or(|min| snowflake::value(&m.id) > min))
+            .filter(|m| max_id.is_none_or(|max| snowflake::value(&m.id) < max))
+            .cloned()
+            .collect();
+        hits.sort_by_key(|m| std::cmp::Reverse(snowflake::value(&m.id)));
+
+        Ok(SearchPage {
+            total_results: hits.len(),
+            messages: hits.into_iter().skip(offset).take(SEARCH_PAGE_SIZE).collect(),
+        })
+    }
+
+    async fn fetch_pins(&self, channel_id: &str) -> Result<Vec<Message>> {
+        Ok(self.state()
+            .messages
+            .get(channel_id)
+            .into_iter()
+            .flatten()
+            .rev()
+            .filter(|m| m.pinned)
+            .cloned()
+            .collect())
+    }
+
+    async fn pin_message(&self, channel_id: &str, message_id: &str) -> Result<()> {
+        self.update_message(channel_id, message_id, |message| message.pinned = true)?;
+        let _ = self.event_tx.send(DiscordEvent::PinsUpdated(channel_id.to_string()));
+        Ok(())
+    }
+
+    async fn unpin_message(&self, channel_id: &str, message_id: &str) -> Result<()> {
+        self.update_message(channel_id, message_id, |message| message.pinned = false)?;
+        let _ = self.event_tx.send(DiscordEvent::PinsUpdated(channel_id.to_string()));
+        Ok(())
+    }
+
+    async fn can_manage_messages(&self, _guild_id: Option<&str>, _channel_id: &str, _user_id: &str) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+fn to_referenced(message: &Message) -> ReferencedMessage {
+    ReferencedMessage::new(&message.id, &message.author, &message.author_id, &message.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn demo() -> (DemoBackend, mpsc::UnboundedReceiver<DiscordEvent>) {
+        DemoBackend::new(None).expect("bundled fixture loads")
+    }
+
+    fn contents(messages: &[Message]) -> Vec<&str> {
+        messages.iter().map(|m| m.content.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn fetch_channels_lists_fixture_channels_and_threads() {
+        let (demo, _rx) = demo();
+
+        let list = demo.fetch_channels("2000").await.unwrap();
+        let mut names: Vec<&str> = list.channels.iter().map(|c| c.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["Lounge", "announcements", "general", "help"]);
+        assert_eq!(list.categories.len(), 1);
+        assert_eq!(list.threads.len(), 2);
+        assert_eq!(list.find("2202").and_then(|c| c.parent_id.as_deref()), Some("2103"));
+
+        assert!(demo.fetch_channels("9999").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fetch_messages_pages_backwards_from_before() {
+        let (demo, _rx) = demo();
+
+        let all = demo.fetch_messages("2101", 50, None).await.unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(all[0].content.starts_with("Welcome to the **demo** server!"));
+        assert_eq!(all[4].content, "Messages can be edited");
+
+        let latest = demo.fetch_messages("2101", 2, None).await.unwrap();
+        assert_eq!(contents(&latest), contents(&all[3..]));
+
+        let older = demo.fetch_messages("2101", 2, Some(&all[3].id)).await.unwrap();
+        assert_eq!(contents(&older), contents(&all[1..3]));
+
+        let oldest = demo.fetch_messages("2101", 50, Some(&all[0].id)).await.unwrap();
+        assert!(oldest.is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_messages_filters_and_orders_newest_first() {
+        let (demo, _rx) = demo();
+        let guild = SearchScope::Guild("2000".to_string());
+
+        let query = SearchQuery { content: "DEMO".to_string(), ..SearchQuery::default() };
+        let page = demo.search_messages(&guild, &query, 0).await.unwrap();
+        assert_eq!(page.total_results, 2);
+        assert_eq!(page.messages[0].channel_id, "2102");
+        assert_eq!(page.messages[1].channel_id, "2101");
+
+        // Threads are part of the guild scope
+        let query = SearchQuery { author_ids: vec!["1002".to_string()], ..SearchQuery::default() };
+        let page = demo.search_messages(&guild, &query, 0).await.unwrap();
+        assert_eq!(
+            contents(&page.messages),
+            [
+                "Messages can be edited",
+                "I call an async fn but nothing happens. What am I missing?",
+                "Does anyone have a good example of a fenced code block?",
+            ]
+        );
+
+        let general = demo.fetch_messages("2101", 50, None).await.unwrap();
+        let query = SearchQuery {
+            channel_ids: vec!["2101".to_string()],
+            min_id: Some(general[0].id.clone()),
+            max_id: Some(general[4].id.clone()),
+            ..SearchQuery::default()
+        };
+        let page = demo.search_messages(&guild, &query, 0).await.unwrap();
+        assert_eq!(page.total_results, 3);
+        assert_eq!(page.messages[0].id, general[3].id);
+
+        let page = demo.search_messages(&SearchScope::Channel("3101".to_string()), &query, 0).await.unwrap();
+        assert_eq!(page.total_results, 0);
+    }
+
+    #[tokio::test]
+    async fn edit_message_emits_message_edited() {
+        let (demo, mut rx) = demo();
+        let id = demo.fetch_messages("2102", 1, None).await.unwrap()[0].id.clone();
+
+        let edited = demo.edit_message("2102", &id, "Edited in the demo").await.unwrap();
+        assert!(edited.edited);
+
+        match rx.try_recv() {
+            Ok(DiscordEvent::MessageEdited(channel_id, message_id, content)) => {
+                assert_eq!((channel_id.as_str(), message_id.as_str()), ("2102", id.as_str()));
+                assert_eq!(content, "Edited in the demo");
+            }
+            _ => panic!("expected a MessageEdited event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn delete_message_emits_message_deleted() {
+        let (demo, mut rx) = demo();
+        let id = demo.fetch_messages("2102", 1, None).await.unwrap()[0].id.clone();
+
+        demo.delete_message("2102", &id).await.unwrap();
+        assert!(demo.fetch_messages("2102", 50, None).await.unwrap().is_empty());
+
+        match rx.try_recv() {
+            Ok(DiscordEvent::MessageDeleted(channel_id, message_id)) => {
+                assert_eq!((channel_id.as_str(), message_id.as_str()), ("2102", id.as_str()));
+            }
+            _ => panic!("expected a MessageDeleted event"),
+        }
+    }
+}
@@ -15,13 +15,15 @@ type WsWrite = futures_util::stream::SplitSink<WsStream, Message>;
 
 pub struct DiscordGateway {
     token: String,
+    api_base: String,
     user: Option<DiscordUser>,
 }
 
 impl DiscordGateway {
-    pub async fn new(token: String) -> Result<Self> {
+    pub async fn new(token: String, api_base: &str) -> Result<Self> {
         Ok(Self {
             token,
+            api_base: api_base.trim_end_matches('/').to_string(),
             user: None,
         })
     }
@@ -108,7 +110,7 @@ impl DiscordGateway {
     async fn get_gateway_url(&self) -> Result<String> {
         let client = reqwest::Client::new();
         let response = client
-            .get("https://discord.com/api/v10/gateway")
+            .get(format!("{}/api/v10/gateway", self.api_base))
             .send()
             .await
             .context("Failed to fetch gateway URL")?;
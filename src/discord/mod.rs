@@ -1,4 +1,10 @@
 pub mod token;
 pub mod api;
+pub mod backend;
+pub mod connection;
+pub mod demo;
 
-pub use api::{DiscordClient, DiscordEvent};
+pub use api::{is_not_found, DiscordClient, DiscordEvent};
+pub use backend::ChatBackend;
+pub use connection::ConnectionState;
+pub use demo::DemoBackend;
@@ -1,14 +1,26 @@
 use anyhow::{Context, Result};
 
+/// Credential Manager target of an account. The default account keeps the plain
+/// `remycord` target it has always had.
 #[cfg(target_os = "windows")]
-pub async fn get_token() -> Result<String> {
+fn windows_target(account: &str) -> String {
+    if account == crate::config::DEFAULT_ACCOUNT {
+        "remycord".to_string()
+    } else {
+        format!("remycord:{}", account)
+    }
+}
+
+/// The token stored for `account`. See `AccountSettings` for how accounts are named.
+#[cfg(target_os = "windows")]
+pub async fn get_token(account: &str) -> Result<String> {
     use windows::Win32::Security::Credentials::{
         CredReadW, CredFree, CREDENTIALW, CRED_TYPE_GENERIC,
     };
     use windows::core::PWSTR;
     
     unsafe {
-        let target = "remycord\0".encode_utf16().collect::<Vec<u16>>();
+        let target = format!("{}\0", windows_target(account)).encode_utf16().collect::<Vec<u16>>();
         let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
         
         CredReadW(
@@ -20,7 +32,7 @@ pub async fn get_token() -> Result<String> {
         .context("Failed to read credential from Windows Credential Manager")?;
         
         if credential.is_null() {
-            anyhow::bail!("Token not found in Windows Credential Manager");
+            anyhow::bail!("No token for account '{}' in Windows Credential Manager", account);
         }
         
         let cred = &*credential;
@@ -37,18 +49,76 @@ pub async fn get_token() -> Result<String> {
     }
 }
 
+/// Save `token` for `account`, replacing any token already stored
+#[cfg(target_os = "windows")]
+pub async fn store_token(account: &str, token: &str) -> Result<()> {
+    use windows::Win32::Security::Credentials::{
+        CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC,
+    };
+    use windows::core::PWSTR;
+    
+    let mut target = format!("{}\0", windows_target(account)).encode_utf16().collect::<Vec<u16>>();
+    let mut user = format!("{}\0", account).encode_utf16().collect::<Vec<u16>>();
+    let mut blob = token.as_bytes().to_vec();
+    
+    let credential = CREDENTIALW {
+        Type: CRED_TYPE_GENERIC,
+        TargetName: PWSTR(target.as_mut_ptr()),
+        UserName: PWSTR(user.as_mut_ptr()),
+        CredentialBlobSize: blob.len() as u32,
+        CredentialBlob: blob.as_mut_ptr(),
+        Persist: CRED_PERSIST_LOCAL_MACHINE,
+        ..Default::default()
+    };
+    
+    unsafe {
+        CredWriteW(&credential, 0)
+            .context("Failed to write credential to Windows Credential Manager")?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub async fn delete_token(account: &str) -> Result<()> {
+    use windows::Win32::Security::Credentials::{CredDeleteW, CRED_TYPE_GENERIC};
+    use windows::core::PWSTR;
+    
+    let target = format!("{}\0", windows_target(account)).encode_utf16().collect::<Vec<u16>>();
+    unsafe {
+        CredDeleteW(PWSTR(target.as_ptr() as *mut u16), CRED_TYPE_GENERIC, 0)
+            .context("Failed to delete credential from Windows Credential Manager")?;
+    }
+    Ok(())
+}
+
 #[cfg(target_os = "macos")]
-pub async fn get_token() -> Result<String> {
+pub async fn get_token(account: &str) -> Result<String> {
     use security_framework::passwords::get_generic_password;
     
-    let password = get_generic_password("remycord", "token")
+    let password = get_generic_password("remycord", account)
         .context("Failed to read token from macOS Keychain")?;
     
     String::from_utf8(password).context("Invalid UTF-8 in stored token")
 }
 
+#[cfg(target_os = "macos")]
+pub async fn store_token(account: &str, token: &str) -> Result<()> {
+    use security_framework::passwords::set_generic_password;
+    
+    set_generic_password("remycord", account, token.as_bytes())
+        .context("Failed to save token to macOS Keychain")
+}
+
+#[cfg(target_os = "macos")]
+pub async fn delete_token(account: &str) -> Result<()> {
+    use security_framework::passwords::delete_generic_password;
+    
+    delete_generic_password("remycord", account)
+        .context("Failed to delete token from macOS Keychain")
+}
+
 #[cfg(target_os = "linux")]
-pub async fn get_token() -> Result<String> {
+pub async fn get_token(account: &str) -> Result<String> {
     use secret_service::SecretService;
     use secret_service::EncryptionType;
     use std::collections::HashMap;
@@ -63,14 +133,14 @@ pub async fn get_token() -> Result<String> {
     
     let mut search_attributes = HashMap::new();
     search_attributes.insert("service", "remycord");
-    search_attributes.insert("username", "token");
+    search_attributes.insert("username", account);
     
     let items = collection.search_items(search_attributes)
         .await
         .context("Failed to search for token")?;
     
     if items.is_empty() {
-        anyhow::bail!("Token not found in keyring");
+        anyhow::bail!("No token for account '{}' in keyring", account);
     }
     
     let item = &items[0];
@@ -81,7 +151,78 @@ pub async fn get_token() -> Result<String> {
     String::from_utf8(secret).context("Invalid UTF-8 in stored token")
 }
 
+#[cfg(target_os = "linux")]
+pub async fn store_token(account: &str, token: &str) -> Result<()> {
+    use secret_service::SecretService;
+    use secret_service::EncryptionType;
+    use std::collections::HashMap;
+    
+    let ss = SecretService::connect(EncryptionType::Dh)
+        .await
+        .context("Failed to connect to Secret Service")?;
+    
+    let collection = ss.get_default_collection()
+        .await
+        .context("Failed to get default collection")?;
+    collection.ensure_unlocked()
+        .await
+        .context("Failed to unlock keyring")?;
+    
+    let mut attributes = HashMap::new();
+    attributes.insert("service", "remycord");
+    attributes.insert("username", account);
+    
+    collection.create_item("Discord Token", attributes, token.as_bytes(), true, "text/plain")
+        .await
+        .context("Failed to save token to keyring")?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub async fn delete_token(account: &str) -> Result<()> {
+    use secret_service::SecretService;
+    use secret_service::EncryptionType;
+    use std::collections::HashMap;
+    
+    let ss = SecretService::connect(EncryptionType::Dh)
+        .await
+        .context("Failed to connect to Secret Service")?;
+    
+    let collection = ss.get_default_collection()
+        .await
+        .context("Failed to get default collection")?;
+    
+    let mut search_attributes = HashMap::new();
+    search_attributes.insert("service", "remycord");
+    search_attributes.insert("username", account);
+    
+    let items = collection.search_items(search_attributes)
+        .await
+        .context("Failed to search for token")?;
+    
+    if items.is_empty() {
+        anyhow::bail!("No token for account '{}' in keyring", account);
+    }
+    
+    for item in items {
+        item.delete()
+            .await
+            .context("Failed to delete token from keyring")?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub async fn get_token(_account: &str) -> Result<String> {
+    anyhow::bail!("Token storage not supported on this platform")
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub async fn store_token(_account: &str, _token: &str) -> Result<()> {
+    anyhow::bail!("Token storage not supported on this platform")
+}
+
 #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-pub async fn get_token() -> Result<String> {
+pub async fn delete_token(_account: &str) -> Result<()> {
     anyhow::bail!("Token storage not supported on this platform")
 }
@@ -3,9 +3,9 @@ use crossterm::event::{KeyEvent, KeyCode, KeyModifiers};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
 
-use crate::app::{App, AppMode, SidebarItem};
+use crate::app::{App, AppMode, DraftField, ForumDraft, PendingAction, SearchState, SidebarItem, TokenSetup};
 use crate::config::{Keybinds, KeyBind, save_config, get_available_themes};
-use crate::models::{Notification, ChannelType};
+use crate::models::{Notification, Channel, ChannelType, Message, ReactionEmoji, ReplyTarget, SendState};
 use super::file_picker;
 
 pub fn handle_keybind_recording(app: &mut App, key: KeyEvent, action: &str) -> Result<bool> {
@@ -53,17 +53,111 @@ pub fn handle_sidebar_input(app: &mut App, key: KeyEvent, kb: &Keybinds) {
         navigate_sidebar_up(app);
     } else if kb.select.matches(key.code, key.modifiers) {
         select_sidebar_item(app);
+    } else if kb.mute_channel.matches(key.code, key.modifiers) {
+        mute_sidebar_item(app);
+    } else if kb.toggle_dnd.matches(key.code, key.modifiers) {
+        toggle_do_not_disturb(app);
+    } else if kb.switch_account.matches(key.code, key.modifiers) {
+        open_accounts(app);
     } else if kb.settings.matches(key.code, key.modifiers) {
         app.mode = AppMode::Settings;
     }
 }
 
+fn open_accounts(app: &mut App) {
+    if app.account.is_none() {
+        app.add_notification(Notification::info("Accounts can't be switched in the demo"));
+        return;
+    }
+    app.accounts_selected_idx = app.account_names()
+        .iter()
+        .position(|name| app.account.as_ref() == Some(name))
+        .unwrap_or(0);
+    app.mode = AppMode::Accounts;
+}
+
+pub fn handle_accounts_input(app: &mut App, key: KeyEvent, kb: &Keybinds) {
+    let names = app.account_names();
+    let count = names.len();
+    let selected = names.get(app.accounts_selected_idx).cloned();
+
+    if !kb.remove_token.matches(key.code, key.modifiers) {
+        app.confirm_token_removal = None;
+    }
+
+    if kb.down.matches(key.code, key.modifiers) || key.code == KeyCode::Down {
+        if app.accounts_selected_idx + 1 < count {
+            app.accounts_selected_idx += 1;
+        }
+    } else if kb.up.matches(key.code, key.modifiers) || key.code == KeyCode::Up {
+        app.accounts_selected_idx = app.accounts_selected_idx.saturating_sub(1);
+    } else if kb.select.matches(key.code, key.modifiers) {
+        app.mode = AppMode::Sidebar;
+        let Some(name) = selected else {
+            return;
+        };
+        // Picking the current account again retries it when it never signed in
+        if app.account.as_ref() != Some(&name) || app.discord_client.is_none() {
+            app.switching_account = Some(name);
+        }
+    } else if kb.set_token.matches(key.code, key.modifiers) {
+        let Some(name) = selected else {
+            return;
+        };
+        let connect = app.account.as_ref() == Some(&name);
+        app.open_token_setup(TokenSetup::new(name, connect));
+    } else if kb.remove_token.matches(key.code, key.modifiers) {
+        let Some(name) = selected else {
+            return;
+        };
+        if app.confirm_token_removal.as_ref() == Some(&name) {
+            app.confirm_token_removal = None;
+            app.removing_token = Some(name);
+        } else {
+            let key = kb.remove_token.key.clone();
+            app.add_notification(Notification::warning(
+                format!("Press {} again to remove the token for '{}' from the keyring", key, name)
+            ));
+            app.confirm_token_removal = Some(name);
+        }
+    }
+}
+
+/// Typing or pasting a token. It is checked and saved by the main loop.
+pub fn handle_token_input(app: &mut App, key: KeyEvent, kb: &Keybinds) {
+    let Some(setup) = app.token_setup.as_mut() else {
+        app.mode = AppMode::Sidebar;
+        return;
+    };
+    if setup.validating {
+        return;
+    }
+
+    if kb.select.matches(key.code, key.modifiers) {
+        if !setup.input.trim().is_empty() {
+            setup.validating = true;
+        }
+    } else if key.code == KeyCode::Backspace {
+        setup.input.pop();
+    } else if key.code == KeyCode::Char('u') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        setup.input.clear();
+    } else if let KeyCode::Char(c) = key.code {
+        if !key.modifiers.contains(KeyModifiers::CONTROL) {
+            setup.input.push(c);
+        }
+    }
+}
+
 pub fn handle_messages_input(
     app: &mut App,
     key: KeyEvent,
     kb: &Keybinds,
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
 ) -> Result<()> {
+    if !kb.delete_message.matches(key.code, key.modifiers) {
+        app.pending_delete = None;
+    }
+
     if kb.scroll_down.matches(key.code, key.modifiers) || key.code == KeyCode::Down {
         scroll_messages_down(app);
     } else if kb.scroll_up.matches(key.code, key.modifiers) || key.code == KeyCode::Up {
@@ -72,6 +166,37 @@ pub fn handle_messages_input(
         app.mode = AppMode::Input;
     } else if kb.attach_file.matches(key.code, key.modifiers) {
         file_picker::pick_file(app, terminal)?;
+    } else if kb.select.matches(key.code, key.modifiers) {
+        jump_to_replied_message(app);
+    } else if kb.reply.matches(key.code, key.modifiers) {
+        start_reply(app);
+    } else if kb.retry_send.matches(key.code, key.modifiers) {
+        retry_selected_message(app);
+    } else if kb.react.matches(key.code, key.modifiers) {
+        if app.selected_message().is_some() && selected_message_is_sent(app) {
+            app.reaction_input.clear();
+            app.mode = AppMode::Reaction;
+        }
+    } else if kb.edit_message.matches(key.code, key.modifiers) {
+        start_editing(app);
+    } else if kb.delete_message.matches(key.code, key.modifiers) {
+        delete_selected_message(app);
+    } else if kb.toggle_pin.matches(key.code, key.modifiers) {
+        if let Some(msg) = app.selected_message().cloned() {
+            if selected_message_is_sent(app) {
+                toggle_pin(app, &msg);
+            }
+        }
+    } else if kb.pins.matches(key.code, key.modifiers) {
+        open_pins(app);
+    } else if kb.search.matches(key.code, key.modifiers) {
+        open_search(app);
+    } else if kb.mute_channel.matches(key.code, key.modifiers) {
+        if let (Some(channel_id), Some(name)) = (app.selected_channel.clone(), app.get_current_channel_name()) {
+            toggle_channel_mute(app, &channel_id, &name);
+        }
+    } else if kb.toggle_dnd.matches(key.code, key.modifiers) {
+        toggle_do_not_disturb(app);
     } else if kb.settings.matches(key.code, key.modifiers) {
         app.mode = AppMode::Settings;
     }
@@ -82,12 +207,22 @@ pub fn handle_input_mode(app: &mut App, key: KeyEvent, kb: &Keybinds) {
     if kb.cancel_input.matches(key.code, key.modifiers) {
         app.mode = AppMode::Messages;
         app.attached_files.clear();
+        app.replying_to = None;
+        if app.editing_message.take().is_some() {
+            app.input.clear();
+            app.input_cursor = 0;
+        }
     } else if kb.send_message.matches(key.code, key.modifiers) {
         app.mode = AppMode::Messages;
+    } else if kb.toggle_reply_mention.matches(key.code, key.modifiers) {
+        if let Some(reply) = app.replying_to.as_mut() {
+            reply.mention = !reply.mention;
+        }
     } else if let KeyCode::Char(c) = key.code {
         if !key.modifiers.contains(KeyModifiers::CONTROL) {
             app.input.insert(app.input_cursor, c);
             app.input_cursor += 1;
+            send_typing(app);
         }
     } else if key.code == KeyCode::Backspace {
         if app.input_cursor > 0 {
@@ -109,6 +244,175 @@ pub fn handle_input_mode(app: &mut App, key: KeyEvent, kb: &Keybinds) {
     }
 }
 
+/// Let the channel know we are typing, at most once per trigger interval
+fn send_typing(app: &mut App) {
+    if app.editing_message.is_some() || !app.should_send_typing() {
+        return;
+    }
+    if let Some(channel_id) = app.selected_channel.clone() {
+        app.pending_actions.push(PendingAction::TriggerTyping { channel_id });
+        app.last_typing_sent = Some(std::time::Instant::now());
+    }
+}
+
+pub fn handle_reaction_input(app: &mut App, key: KeyEvent, kb: &Keybinds) {
+    if kb.cancel_input.matches(key.code, key.modifiers) {
+        app.reaction_input.clear();
+        app.mode = AppMode::Messages;
+    } else if key.code == KeyCode::Enter {
+        toggle_reaction(app);
+        app.reaction_input.clear();
+        app.mode = AppMode::Messages;
+    } else if key.code == KeyCode::Backspace {
+        app.reaction_input.pop();
+    } else if let KeyCode::Char(c) = key.code {
+        if !key.modifiers.contains(KeyModifiers::CONTROL) {
+            app.reaction_input.push(c);
+        }
+    }
+}
+
+fn open_pins(app: &mut App) {
+    let Some(channel_id) = app.selected_channel.as_deref() else {
+        return;
+    };
+    if !app.pins.contains_key(channel_id) {
+        app.loading_pins = true;
+    }
+    app.pins_selected_idx = 0;
+    app.mode = AppMode::Pins;
+}
+
+pub fn handle_pins_input(app: &mut App, key: KeyEvent, kb: &Keybinds) {
+    let pin_count = app.current_pins().len();
+
+    if kb.down.matches(key.code, key.modifiers) || key.code == KeyCode::Down {
+        if app.pins_selected_idx + 1 < pin_count {
+            app.pins_selected_idx += 1;
+        }
+    } else if kb.up.matches(key.code, key.modifiers) || key.code == KeyCode::Up {
+        app.pins_selected_idx = app.pins_selected_idx.saturating_sub(1);
+    } else if kb.select.matches(key.code, key.modifiers) {
+        jump_to_pin(app);
+    } else if kb.toggle_pin.matches(key.code, key.modifiers) {
+        if let Some(msg) = app.current_pins().get(app.pins_selected_idx).cloned() {
+            toggle_pin(app, &msg);
+        }
+    }
+}
+
+fn jump_to_pin(app: &mut App) {
+    let Some(message_id) = app.current_pins().get(app.pins_selected_idx).map(|m| m.id.clone()) else {
+        return;
+    };
+    app.mode = AppMode::Messages;
+    jump_to_message(app, message_id);
+}
+
+/// Scroll the open channel to a message, paging back through history until it is loaded
+fn jump_to_message(app: &mut App, message_id: String) {
+    if app.scroll_to_message(&message_id) {
+        return;
+    }
+    let Some(channel_id) = app.selected_channel.as_deref() else {
+        return;
+    };
+    if app.loading_messages {
+        // The first page is still on its way; the search carries on once it lands
+        app.jump_target = Some(message_id);
+    } else if app.is_history_exhausted(channel_id) {
+        app.add_notification(Notification::warning("Message was not found in the channel history"));
+    } else {
+        app.jump_target = Some(message_id);
+        app.loading_older_messages = true;
+        app.add_notification(Notification::info("Loading older messages..."));
+    }
+}
+
+/// Open the search overlay, picking up the previous search if it covers the same place
+fn open_search(app: &mut App) {
+    let Some(scope) = app.search_scope() else {
+        return;
+    };
+    if app.search.as_ref().is_none_or(|s| s.scope != scope) {
+        app.search = Some(SearchState::new(scope));
+    }
+    app.mode = AppMode::Search;
+}
+
+pub fn handle_search_input(app: &mut App, key: KeyEvent, kb: &Keybinds) {
+    let Some(search) = app.search.as_mut() else {
+        app.mode = AppMode::Messages;
+        return;
+    };
+
+    if kb.select.matches(key.code, key.modifiers) {
+        submit_search(app);
+    } else if key.code == KeyCode::Down {
+        if search.selected_idx + 1 < search.results.len() {
+            search.selected_idx += 1;
+        }
+    } else if key.code == KeyCode::Up {
+        search.selected_idx = search.selected_idx.saturating_sub(1);
+    } else if key.code == KeyCode::Tab {
+        search.turn_page(true);
+    } else if key.code == KeyCode::BackTab {
+        search.turn_page(false);
+    } else if key.code == KeyCode::Backspace {
+        search.input.pop();
+    } else if let KeyCode::Char(c) = key.code {
+        if !key.modifiers.contains(KeyModifiers::CONTROL) {
+            search.input.push(c);
+        }
+    }
+}
+
+/// Run the typed query, or jump to the selected hit if it has not changed since the last run
+fn submit_search(app: &mut App) {
+    let query = match app.build_search_query() {
+        Ok(query) => query,
+        Err(e) => {
+            app.add_notification(Notification::warning(e.to_string()));
+            return;
+        }
+    };
+    let Some(search) = app.search.as_mut() else {
+        return;
+    };
+
+    if search.query.as_ref() != Some(&query) || search.results.is_empty() {
+        search.query = Some(query);
+        search.page = 0;
+        search.loading = true;
+        return;
+    }
+
+    let Some(hit) = search.selected_result().cloned() else {
+        return;
+    };
+    if app.selected_channel.as_deref() != Some(hit.channel_id.as_str()) {
+        open_channel(app, &hit.channel_id);
+    }
+    app.mode = AppMode::Messages;
+    jump_to_message(app, hit.id);
+}
+
+fn toggle_pin(app: &mut App, msg: &Message) {
+    if !app.can_pin(&msg.channel_id) {
+        app.add_notification(Notification::info("You do not have permission to pin messages here"));
+        return;
+    }
+
+    let channel_id = msg.channel_id.clone();
+    let message_id = msg.id.clone();
+
+    if msg.pinned {
+        app.pending_actions.push(PendingAction::UnpinMessage { channel_id, message_id });
+    } else {
+        app.pending_actions.push(PendingAction::PinMessage { channel_id, message_id });
+    }
+}
+
 pub fn handle_settings_input(app: &mut App, key: KeyEvent, kb: &Keybinds) -> Result<()> {
     if key.code == KeyCode::Down || kb.down.matches(key.code, key.modifiers) {
         let mut next = app.settings_selected + 1;
@@ -178,6 +482,50 @@ fn adjust_sidebar_scroll(app: &mut App, visible_items: usize) {
     }
 }
 
+fn mute_sidebar_item(app: &mut App) {
+    let items = app.get_sidebar_items();
+    let target = match items.get(app.selected_sidebar_idx) {
+        Some(SidebarItem::DmChannel(dm)) => Some((dm.id.clone(), dm.display_name())),
+        Some(SidebarItem::Channel { channel, .. }) | Some(SidebarItem::Thread { thread: channel, .. }) => {
+            Some((channel.id.clone(), format!("#{}", channel.name)))
+        }
+        _ => None,
+    };
+
+    if let Some((channel_id, name)) = target {
+        toggle_channel_mute(app, &channel_id, &name);
+    }
+}
+
+fn toggle_channel_mute(app: &mut App, channel_id: &str, name: &str) {
+    let muted = app.config.notifications.toggle_mute(channel_id);
+    let message = if muted {
+        format!("Muted notifications from {}", name)
+    } else {
+        format!("Unmuted notifications from {}", name)
+    };
+    app.add_notification(Notification::info(message));
+    save_notification_settings(app);
+}
+
+fn toggle_do_not_disturb(app: &mut App) {
+    let settings = &mut app.config.notifications;
+    settings.do_not_disturb = !settings.do_not_disturb;
+    let message = if settings.do_not_disturb {
+        "Do not disturb on"
+    } else {
+        "Do not disturb off"
+    };
+    app.add_notification(Notification::info(message));
+    save_notification_settings(app);
+}
+
+fn save_notification_settings(app: &mut App) {
+    if let Err(e) = save_config(&app.config) {
+        app.add_notification(Notification::error(format!("Failed to save config: {}", e)));
+    }
+}
+
 fn select_sidebar_item(app: &mut App) {
     let items = app.get_sidebar_items();
     if let Some(item) = items.get(app.selected_sidebar_idx) {
@@ -189,17 +537,7 @@ fn select_sidebar_item(app: &mut App) {
                 }
             }
             SidebarItem::DmChannel(dm) => {
-                app.selected_channel = Some(dm.id.clone());
-                app.message_scroll = 0;
-                
-                if let Some(messages) = app.message_cache.get(&dm.id) {
-                    app.messages = messages.clone();
-                } else {
-                    app.messages.clear();
-                    app.loading_messages = true;
-                }
-                
-                app.mode = AppMode::Messages;
+                open_channel(app, &dm.id);
             }
             SidebarItem::ServerSection => {}
             SidebarItem::Server(guild) => {
@@ -214,7 +552,7 @@ fn select_sidebar_item(app: &mut App) {
             SidebarItem::Category { guild_id, category, .. } => {
                 app.toggle_category(guild_id, &category.id);
             }
-            SidebarItem::Channel { channel, .. } => {
+            SidebarItem::Channel { channel, .. } | SidebarItem::Thread { thread: channel, .. } => {
                 if !channel.is_text_based() {
                     app.add_notification(Notification::info(
                         format!("Cannot view messages in {} channels", 
@@ -228,28 +566,311 @@ fn select_sidebar_item(app: &mut App) {
                     return;
                 }
                 
-                app.selected_channel = Some(channel.id.clone());
-                app.message_scroll = 0;
-                
-                if let Some(messages) = app.message_cache.get(&channel.id) {
-                    app.messages = messages.clone();
+                if channel.kind == ChannelType::Forum {
+                    open_forum(app, &channel.id);
                 } else {
-                    app.messages.clear();
-                    app.loading_messages = true;
+                    open_channel(app, &channel.id);
                 }
-                
-                app.mode = AppMode::Messages;
             }
         }
     }
 }
 
-pub fn exit_channel(app: &mut App) {
+fn open_channel(app: &mut App, channel_id: &str) {
+    app.selected_channel = Some(channel_id.to_string());
+    app.message_scroll = 0;
+    app.jump_target = None;
+    
+    if let Some(messages) = app.message_cache.get(channel_id) {
+        app.messages = messages.clone();
+    } else {
+        app.messages.clear();
+        app.loading_messages = true;
+    }
+    app.mark_channel_read(channel_id);
+    
+    app.mode = AppMode::Messages;
+}
+
+fn open_forum(app: &mut App, forum_id: &str) {
+    app.selected_forum = Some(forum_id.to_string());
+    app.forum_selected_idx = 0;
+    
+    // Refetched on every open, since other people's new posts only reach us as thread updates
+    app.loading_forum_posts = true;
+    
+    app.mode = AppMode::Forum;
+}
+
+pub fn exit_forum(app: &mut App) {
     app.mode = AppMode::Sidebar;
+    app.selected_forum = None;
+    app.forum_selected_idx = 0;
+}
+
+pub fn handle_forum_input(app: &mut App, key: KeyEvent, kb: &Keybinds) {
+    let post_count = app.current_forum_posts().len();
+
+    if kb.down.matches(key.code, key.modifiers) || key.code == KeyCode::Down {
+        if app.forum_selected_idx + 1 < post_count {
+            app.forum_selected_idx += 1;
+        }
+    } else if kb.up.matches(key.code, key.modifiers) || key.code == KeyCode::Up {
+        app.forum_selected_idx = app.forum_selected_idx.saturating_sub(1);
+    } else if kb.select.matches(key.code, key.modifiers) {
+        open_forum_post(app);
+    } else if kb.new_post.matches(key.code, key.modifiers) {
+        if let Some(forum_id) = app.selected_forum.clone() {
+            app.forum_draft = Some(ForumDraft::new(forum_id));
+            app.mode = AppMode::ForumCompose;
+        }
+    } else if kb.settings.matches(key.code, key.modifiers) {
+        app.mode = AppMode::Settings;
+    }
+}
+
+fn open_forum_post(app: &mut App) {
+    let Some(post) = app.current_forum_posts().get(app.forum_selected_idx).cloned() else {
+        return;
+    };
+
+    // Posts outside the active thread list still need a name for the header
+    if let Some(guild_id) = app.guild_id_for_channel(&post.forum_id) {
+        let thread = Channel::new(post.id.clone(), post.title.clone(), ChannelType::Thread, 0)
+            .with_parent(post.forum_id.clone());
+        app.upsert_thread(&guild_id, thread);
+    }
+
+    open_channel(app, &post.id);
+}
+
+pub fn handle_forum_compose(app: &mut App, key: KeyEvent, kb: &Keybinds) {
+    let tag_count = app.current_forum().map(|f| f.tags.len()).unwrap_or(0);
+    let tag_ids: Vec<String> = app
+        .current_forum()
+        .map(|f| f.tags.iter().map(|t| t.id.clone()).collect())
+        .unwrap_or_default();
+
+    let Some(draft) = app.forum_draft.as_mut() else {
+        app.mode = AppMode::Forum;
+        return;
+    };
+
+    if kb.cancel_input.matches(key.code, key.modifiers) {
+        app.forum_draft = None;
+        app.mode = AppMode::Forum;
+    } else if key.code == KeyCode::Tab {
+        draft.next_field();
+        if draft.field == DraftField::Tags && tag_count == 0 {
+            draft.next_field();
+        }
+    } else if key.code == KeyCode::Enter {
+        if draft.is_ready() {
+            let draft = app.forum_draft.take().unwrap();
+            app.pending_actions.push(PendingAction::CreateForumPost {
+                forum_id: draft.forum_id,
+                title: draft.title.trim().to_string(),
+                content: draft.content,
+                tag_ids: draft.tag_ids,
+            });
+            app.mode = AppMode::Forum;
+        } else {
+            app.add_notification(Notification::warning("A post needs a title and a message"));
+        }
+    } else if draft.field == DraftField::Tags {
+        match key.code {
+            KeyCode::Left => draft.tag_cursor = draft.tag_cursor.saturating_sub(1),
+            KeyCode::Right if draft.tag_cursor + 1 < tag_count => draft.tag_cursor += 1,
+            KeyCode::Char(' ') => {
+                if let Some(tag_id) = tag_ids.get(draft.tag_cursor) {
+                    draft.toggle_tag(tag_id);
+                }
+            }
+            _ => {}
+        }
+    } else {
+        let text = if draft.field == DraftField::Title {
+            &mut draft.title
+        } else {
+            &mut draft.content
+        };
+
+        match key.code {
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => text.push(c),
+            _ => {}
+        }
+    }
+}
+
+pub fn exit_channel(app: &mut App) {
+    app.loading_older_messages = false;
+    app.jump_target = None;
+    app.mode = if app.selected_forum.is_some() {
+        AppMode::Forum
+    } else {
+        AppMode::Sidebar
+    };
     app.selected_channel = None;
     app.messages.clear();
     app.message_scroll = 0;
     app.attached_files.clear();
+    app.pending_delete = None;
+    app.replying_to = None;
+    if app.editing_message.take().is_some() {
+        app.input.clear();
+        app.input_cursor = 0;
+    }
+}
+
+/// Whether the selected message has made it to Discord, telling the user if not
+fn selected_message_is_sent(app: &mut App) -> bool {
+    let unsent = app.selected_message().is_some_and(|m| m.send_state.is_some());
+    if unsent {
+        app.add_notification(Notification::info("This message has not been sent yet"));
+    }
+    !unsent
+}
+
+fn start_editing(app: &mut App) {
+    if !selected_message_is_sent(app) {
+        return;
+    }
+    let Some(msg) = app.selected_message() else {
+        return;
+    };
+
+    if !app.is_own_message(msg) {
+        app.add_notification(Notification::info("You can only edit your own messages"));
+        return;
+    }
+
+    let message_id = msg.id.clone();
+    let content = msg.content.clone();
+
+    app.replying_to = None;
+    app.editing_message = Some(message_id);
+    app.input_cursor = content.len();
+    app.input = content;
+    app.attached_files.clear();
+    app.mode = AppMode::Input;
+}
+
+fn retry_selected_message(app: &mut App) {
+    let Some(msg) = app.selected_message() else {
+        return;
+    };
+
+    if matches!(msg.send_state, Some(SendState::Failed(_))) {
+        let (channel_id, nonce) = (msg.channel_id.clone(), msg.id.clone());
+        app.retry_message(&channel_id, &nonce);
+    }
+}
+
+fn start_reply(app: &mut App) {
+    if !selected_message_is_sent(app) {
+        return;
+    }
+    let Some(msg) = app.selected_message() else {
+        return;
+    };
+
+    let target = ReplyTarget::new(msg.id.clone(), msg.author.clone());
+
+    if app.editing_message.take().is_some() {
+        app.input.clear();
+        app.input_cursor = 0;
+    }
+    app.replying_to = Some(target);
+    app.mode = AppMode::Input;
+}
+
+fn jump_to_replied_message(app: &mut App) {
+    let Some(reply) = app.selected_message().and_then(|m| m.reply_to.clone()) else {
+        return;
+    };
+
+    if let Some(idx) = app.messages.iter().position(|m| m.id == reply.id) {
+        app.message_scroll = idx;
+    } else {
+        app.add_notification(Notification::info("Original message is not loaded"));
+    }
+}
+
+/// Resolve the reaction prompt against the selected message and queue an add or remove.
+/// Accepts the position of an existing reaction, an existing `:name:`, or a new emoji.
+fn toggle_reaction(app: &mut App) {
+    let Some(msg) = app.selected_message() else {
+        return;
+    };
+
+    let input = app.reaction_input.trim();
+    let existing = input
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|idx| msg.reactions.get(idx))
+        .or_else(|| msg.reactions.iter().find(|r| r.emoji.label() == input))
+        .map(|r| r.emoji.clone());
+
+    let Some(emoji) = existing.or_else(|| ReactionEmoji::parse(input)) else {
+        if !input.is_empty() {
+            let text = format!("'{}' is not an emoji or a reaction on this message", input);
+            app.add_notification(Notification::warning(text));
+        }
+        return;
+    };
+
+    let channel_id = msg.channel_id.clone();
+    let message_id = msg.id.clone();
+
+    if msg.has_reacted(&emoji) {
+        app.pending_actions.push(PendingAction::RemoveReaction { channel_id, message_id, emoji });
+    } else {
+        app.pending_actions.push(PendingAction::AddReaction { channel_id, message_id, emoji });
+    }
+}
+
+fn delete_selected_message(app: &mut App) {
+    let Some(msg) = app.selected_message() else {
+        return;
+    };
+
+    // Unsent messages are only in the outbox, so drop them without asking
+    match msg.send_state.clone() {
+        Some(SendState::Sending) => {
+            app.add_notification(Notification::info("This message is still sending"));
+            return;
+        }
+        Some(_) => {
+            let (channel_id, nonce) = (msg.channel_id.clone(), msg.id.clone());
+            app.discard_message(&channel_id, &nonce);
+            app.add_notification(Notification::info("Discarded unsent message"));
+            return;
+        }
+        None => {}
+    }
+
+    if !app.is_own_message(msg) {
+        app.add_notification(Notification::info("You can only delete your own messages"));
+        return;
+    }
+
+    let channel_id = msg.channel_id.clone();
+    let message_id = msg.id.clone();
+
+    if app.pending_delete.as_deref() == Some(message_id.as_str()) {
+        app.pending_delete = None;
+        app.pending_actions.push(PendingAction::DeleteMessage { channel_id, message_id });
+    } else {
+        let key = app.config.keybinds.delete_message.key.clone();
+        app.pending_delete = Some(message_id);
+        app.add_notification(Notification::warning(
+            format!("Press {} again to delete this message", key)
+        ));
+    }
 }
 
 fn scroll_messages_down(app: &mut App) {
@@ -264,6 +885,10 @@ fn scroll_messages_up(app: &mut App) {
     let speed = app.config.general.message_scroll_speed;
     if app.message_scroll > 0 {
         app.message_scroll = app.message_scroll.saturating_sub(speed);
+    } else if let Some(channel_id) = &app.selected_channel {
+        if !app.loading_messages && !app.is_history_exhausted(channel_id) {
+            app.loading_older_messages = true;
+        }
     }
 }
 
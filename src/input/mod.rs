@@ -43,11 +43,54 @@ pub fn handle_input(
         AppMode::Input => {
             handlers::handle_input_mode(app, key, kb);
         }
+        AppMode::Reaction => {
+            handlers::handle_reaction_input(app, key, kb);
+        }
+        AppMode::Pins => {
+            if should_quit || kb.back.matches(key.code, key.modifiers) {
+                app.mode = AppMode::Messages;
+            } else {
+                handlers::handle_pins_input(app, key, kb);
+            }
+        }
+        AppMode::Search => {
+            if kb.cancel_input.matches(key.code, key.modifiers) {
+                app.mode = AppMode::Messages;
+            } else {
+                handlers::handle_search_input(app, key, kb);
+            }
+        }
+        AppMode::Forum => {
+            if should_quit || kb.back.matches(key.code, key.modifiers) {
+                handlers::exit_forum(app);
+            } else {
+                handlers::handle_forum_input(app, key, kb);
+            }
+        }
+        AppMode::ForumCompose => {
+            handlers::handle_forum_compose(app, key, kb);
+        }
+        AppMode::Accounts => {
+            if should_quit || kb.back.matches(key.code, key.modifiers) {
+                app.mode = AppMode::Sidebar;
+            } else {
+                handlers::handle_accounts_input(app, key, kb);
+            }
+        }
+        AppMode::TokenSetup => {
+            if kb.cancel_input.matches(key.code, key.modifiers) {
+                app.close_token_setup();
+            } else {
+                handlers::handle_token_input(app, key, kb);
+            }
+        }
         AppMode::Settings => {
             if should_quit || key.code == KeyCode::Esc {
                 save_config(&app.config)?;
                 app.mode = if app.selected_channel.is_some() {
                     AppMode::Messages
+                } else if app.selected_forum.is_some() {
+                    AppMode::Forum
                 } else {
                     AppMode::Sidebar
                 };
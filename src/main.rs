@@ -4,6 +4,8 @@ mod ui;
 mod models;
 mod input;
 mod discord;
+mod notify;
+mod archive;
 
 use anyhow::Result;
 use crossterm::{
@@ -13,14 +15,23 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
 
-use app::{App, AppMode};
+use app::{App, AppMode, PendingAction, TokenSetup};
 use config::load_config;
 use input::handle_input;
-use discord::{DiscordClient, DiscordEvent};
-use models::Notification;
+use discord::{ChatBackend, ConnectionState, DemoBackend, DiscordClient, DiscordEvent};
+use models::{Embed, Message, Notification, OutgoingMessage, SendState};
+use models::snowflake;
+use models::mention::{find_mentions, Mention};
+
+// Number of messages requested per page of channel history
+const MESSAGE_PAGE_SIZE: u8 = 50;
+
+// Pages fetched per channel when catching up after a reconnect
+const MAX_BACKFILL_PAGES: usize = 4;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -29,31 +40,38 @@ async fn main() -> Result<()> {
         config::Config::default()
     });
 
-    // Await the async get_token
-    let token = match discord::token::get_token().await {
-        Ok(token) => {
-            println!("Found Discord token, connecting...");
-            token
+    let args = parse_args();
+    let account = args.account.unwrap_or_else(|| config.accounts.default.clone());
+    config::check_account_name(&account)?;
+    
+    // Without a token we start on the token screen, with nothing to connect yet
+    let mut missing_token = None;
+    let chat_backend = match &args.demo {
+        Some(fixture) => {
+            let (demo_backend, event_rx) = DemoBackend::new(fixture.as_deref())?;
+            let client: Box<dyn ChatBackend> = Box::new(demo_backend);
+            Some((client, event_rx))
         }
-        Err(e) => {
-            eprintln!("No Discord token found: {}", e);
-            eprintln!("To set up your token, run one of these commands:");
-            eprintln!("");
-            if cfg!(target_os = "windows") {
-                eprintln!("  cmdkey /add:remycord /user:token /pass:YOUR_DISCORD_TOKEN");
-            } else if cfg!(target_os = "macos") {
-                eprintln!("  security add-generic-password -s remycord -a token -w \"YOUR_DISCORD_TOKEN\"");
-            } else {
-                eprintln!("  secret-tool store --label=\"Discord Token\" service remycord username token");
+        None => match discord::token::get_token(&account).await {
+            Ok(token) => {
+                println!("Found Discord token for '{}', connecting...", account);
+                let (client, event_rx) = DiscordClient::new(token, &config.network).await?;
+                let client: Box<dyn ChatBackend> = Box::new(client);
+                Some((client, event_rx))
             }
-            eprintln!("");
-            return Ok(());
-        }
+            Err(e) => {
+                missing_token = Some(e);
+                None
+            }
+        },
     };
-
-    let (discord_client, mut event_rx) = DiscordClient::new(token.clone()).await?;
-    
-    discord_client.start_gateway(token.clone()).await?;
+    let mut reachable = true;
+    if let Some((client, _)) = &chat_backend {
+        reachable = client.is_reachable().await;
+        
+        // Keeps retrying in the background when offline, and takes us back online once it connects
+        client.start_gateway().await?;
+    }
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -62,17 +80,42 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     let mut app = App::new(config);
-    app.set_discord_client(discord_client);
+    let event_rx = chat_backend.map(|(client, event_rx)| {
+        app.set_discord_client(client);
+        event_rx
+    });
+    if args.demo.is_none() {
+        app.account = Some(account.clone());
+    }
+    // Scripted demo data has no place in the real archive, and until we sign in
+    // there is nothing to save in it
+    if app.discord_client.is_some() && args.demo.is_none() {
+        match archive::MessageArchive::open(&account) {
+            Ok(archive) => app.archive = Some(archive),
+            Err(e) => app.add_notification(Notification::warning(
+                format!("Message archive unavailable: {}", e)
+            )),
+        }
+    }
+    if let Some(e) = missing_token {
+        app.open_token_setup(TokenSetup::new(account.clone(), true).with_status(e.to_string()));
+    }
+    if !reachable {
+        go_offline(&mut app).await;
+    }
+    if app.config.notifications.enabled {
+        match notify::DesktopNotifier::connect().await {
+            Ok(notifier) => app.desktop_notifier = Some(notifier),
+            Err(e) => app.add_notification(Notification::warning(
+                format!("Desktop notifications unavailable: {}", e)
+            )),
+        }
+    }
     
     let app = Arc::new(Mutex::new(app));
-    let app_clone = app.clone();
-    
-    tokio::spawn(async move {
-        while let Some(event) = event_rx.recv().await {
-            let mut app = app_clone.lock().await;
-            handle_discord_event(&mut app, event).await;
-        }
-    });
+    if let Some(event_rx) = event_rx {
+        spawn_event_forwarder(app.clone(), event_rx, 0);
+    }
 
     let res = run_app(&mut terminal, app).await;
 
@@ -91,50 +134,251 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+struct Args {
+    /// `--demo [fixture.yaml]` runs against scripted data instead of Discord. The inner
+    /// `None` means the bundled fixture.
+    demo: Option<Option<PathBuf>>,
+    /// `--account NAME` picks the keyring entry to sign in with
+    account: Option<String>,
+}
+
+fn parse_args() -> Args {
+    let mut parsed = Args { demo: None, account: None };
+    let mut args = std::env::args().skip(1).peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--demo" => {
+                parsed.demo = Some(args.next_if(|next| !next.starts_with("--")).map(PathBuf::from));
+            }
+            "--account" => parsed.account = args.next(),
+            _ => {}
+        }
+    }
+    parsed
+}
+
+/// Feed backend events to the app until the account they belong to is switched away from
+fn spawn_event_forwarder(
+    app: Arc<Mutex<App>>,
+    mut event_rx: mpsc::UnboundedReceiver<DiscordEvent>,
+    generation: u64,
+) {
+    tokio::spawn(async move {
+        while let Some(event) = event_rx.recv().await {
+            let mut app = app.lock().await;
+            if app.account_generation != generation {
+                break;
+            }
+            handle_discord_event(&mut app, event).await;
+        }
+    });
+}
+
+/// Sign out of the current account and start over as `account`, asking for its token
+/// when the keyring has none
+async fn switch_account(app_arc: &Arc<Mutex<App>>, account: String) {
+    let token = match discord::token::get_token(&account).await {
+        Ok(token) => token,
+        Err(e) => {
+            app_arc.lock().await.open_token_setup(TokenSetup::new(account, true).with_status(e.to_string()));
+            return;
+        }
+    };
+    if connect_account(app_arc, &account, token).await {
+        app_arc.lock().await.add_notification(Notification::success(format!("Switched to {}", account)));
+    }
+}
+
+/// Replace whatever we are signed in as with `account`, returning whether it worked
+async fn connect_account(app_arc: &Arc<Mutex<App>>, account: &str, token: String) -> bool {
+    let network = app_arc.lock().await.config.network.clone();
+    
+    let (client, event_rx) = match DiscordClient::new(token, &network).await {
+        Ok(client) => client,
+        Err(e) => {
+            app_arc.lock().await.add_notification(Notification::error(
+                format!("Could not sign in as '{}': {}", account, e)
+            ));
+            return false;
+        }
+    };
+    let reachable = client.is_reachable().await;
+    // Events wait in the channel until the forwarder below picks them up
+    if let Err(e) = client.start_gateway().await {
+        app_arc.lock().await.add_notification(Notification::error(
+            format!("Could not sign in as '{}': {}", account, e)
+        ));
+        return false;
+    }
+    
+    let mut app = app_arc.lock().await;
+    save_archive(&mut app).await;
+    if let Some(old_client) = app.discord_client.take() {
+        old_client.lock().await.shutdown().await;
+    }
+    
+    app.reset_for_account(account.to_string());
+    app.set_discord_client(Box::new(client));
+    match archive::MessageArchive::open(account) {
+        Ok(archive) => app.archive = Some(archive),
+        Err(e) => app.add_notification(Notification::warning(
+            format!("Message archive unavailable: {}", e)
+        )),
+    }
+    if !reachable {
+        go_offline(&mut app).await;
+    }
+    
+    spawn_event_forwarder(app_arc.clone(), event_rx, app.account_generation);
+    true
+}
+
+/// Check the token typed on the token screen, save it, and sign in with it if asked to
+async fn submit_token(app_arc: &Arc<Mutex<App>>, setup: TokenSetup) {
+    let network = app_arc.lock().await.config.network.clone();
+    let token = setup.input.trim().to_string();
+    
+    let result = match DiscordClient::validate_token(&token, &network).await {
+        Ok(user_name) => discord::token::store_token(&setup.account, &token)
+            .await
+            .map(|_| user_name)
+            .map_err(|e| anyhow::anyhow!("Could not save the token: {}", e)),
+        Err(e) => Err(e),
+    };
+    let user_name = match result {
+        Ok(user_name) => user_name,
+        Err(e) => {
+            if let Some(current) = app_arc.lock().await.token_setup.as_mut() {
+                current.validating = false;
+                current.status = Some(e.to_string());
+            }
+            return;
+        }
+    };
+    
+    app_arc.lock().await.close_token_setup();
+    if setup.connect {
+        if connect_account(app_arc, &setup.account, token).await {
+            app_arc.lock().await.add_notification(Notification::success(
+                format!("Signed in as {} ({})", user_name, setup.account)
+            ));
+        }
+    } else {
+        app_arc.lock().await.add_notification(Notification::success(
+            format!("Saved token for {} ({})", setup.account, user_name)
+        ));
+    }
+}
+
+/// Forget the token stored for `account`. The current session stays signed in until exit.
+async fn remove_token(app: &mut App, account: &str) {
+    match discord::token::delete_token(account).await {
+        Ok(()) => app.add_notification(Notification::success(
+            format!("Removed the token for {} from the keyring", account)
+        )),
+        Err(e) => app.add_notification(Notification::error(
+            format!("Could not remove the token for {}: {}", account, e)
+        )),
+    }
+}
+
 async fn handle_discord_event(app: &mut App, event: DiscordEvent) {
     match event {
         DiscordEvent::Ready(guilds) => {
             for guild in guilds {
+                if app.config.images.enabled && app.config.images.render_server_icons {
+                    if let Some(icon) = guild.icon.as_deref() {
+                        let _ = app.image_renderer.load_server_icon(&guild.id, icon).await;
+                    }
+                }
+                
                 if !app.guilds.iter().any(|g| g.id == guild.id) {
                     app.guilds.push(guild);
                 } else {
                     if let Some(g) = app.guilds.iter_mut().find(|g| g.id == guild.id) {
                         g.name = guild.name;
+                        g.icon = guild.icon;
                     }
                 }
             }
         }
-        DiscordEvent::Connected(username) => {
+        DiscordEvent::Connected(user_id, username) => {
+            app.current_user_id = Some(user_id);
             app.add_notification(Notification::success(format!("Connected as {}", username)));
+            app.current_user_name = Some(username);
+        }
+        DiscordEvent::ConnectionChanged(state) => {
+            // A new session after we were connected may have missed messages
+            if state.is_ready() && !app.connection.is_ready() && app.current_user_id.is_some() {
+                app.backfill_pending = true;
+            }
+            if state.is_ready() && app.offline {
+                app.offline = false;
+                // The sidebar is still the archived one, so fetch it afresh
+                app.channel_cache.clear();
+                app.loading_dms = true;
+                app.loading_channels = true;
+                if let Some(channel_id) = app.selected_channel.clone() {
+                    if app.message_cache.contains_key(&channel_id) {
+                        app.reconcile_pending = Some(channel_id);
+                    } else {
+                        app.loading_messages = true;
+                    }
+                }
+                app.add_notification(Notification::success("Back online"));
+            }
+            match &state {
+                ConnectionState::Retrying { reason, .. } => {
+                    app.add_notification(Notification::warning(format!("Gateway disconnected: {}", reason)));
+                }
+                ConnectionState::Disconnected(reason) => {
+                    app.add_notification(Notification::error(format!("Disconnected: {}", reason)));
+                }
+                _ => {}
+            }
+            app.connection = state;
+        }
+        DiscordEvent::MessageSent(nonce, msg) => {
+            if app.reconcile_sent(&nonce, msg.clone()) {
+                load_message_images(app, std::slice::from_ref(&msg)).await;
+            }
+        }
+        DiscordEvent::SendFailed(channel_id, nonce, error) => {
+            app.set_send_state(&channel_id, &nonce, SendState::Failed(error.clone()));
+            app.add_notification(Notification::error(format!("Failed to send message: {}", error)));
         }
         DiscordEvent::NewMessage(msg) => {
-            if Some(&msg.channel_id) == app.selected_channel.as_ref() {
-                let min = (
-                    app.config.images.min_image_width as u32,
-                    app.config.images.min_image_height as u32,
-                );
-                let max = (
-                    app.config.images.max_image_width as u32,
-                    app.config.images.max_image_height as u32,
-                );
-
-                if app.config.images.enabled && app.config.images.render_avatars {
-                    let _ = app.image_renderer.load_avatar(
-                        &msg.author_id,
-                        msg.author_avatar.as_deref(),
-                    ).await;
+            // Our own sends echo back with their nonce, and may beat the HTTP response
+            if let Some(nonce) = msg.nonce.clone() {
+                if app.reconcile_sent(&nonce, msg.clone()) {
+                    load_message_images(app, std::slice::from_ref(&msg)).await;
+                    return;
                 }
-                
-                if app.config.images.enabled && app.config.images.render_attachments {
-                    for attachment in msg.attachments.iter().filter(|a| a.is_image()) {
-                        let _ = app.image_renderer.load_attachment(
-                            &attachment.id,
-                            &attachment.url,
-                            min,
-                            max,
-                        ).await;
-                    }
+            }
+            
+            // Resumed sessions replay events that a backfill may already have fetched
+            let seen = app.message_cache
+                .get(&msg.channel_id)
+                .is_some_and(|messages| messages.iter().any(|m| m.id == msg.id))
+                || (app.selected_channel.as_deref() == Some(msg.channel_id.as_str())
+                    && app.messages.iter().any(|m| m.id == msg.id));
+            if seen {
+                return;
+            }
+            
+            app.stop_typing(&msg.channel_id, &msg.author_id);
+            resolve_mentions(app, std::slice::from_ref(&msg)).await;
+            load_member_roles(app, std::slice::from_ref(&msg)).await;
+            app.track_unread(&msg);
+            
+            if let Some(notifier) = &app.desktop_notifier {
+                if let Some(title) = app.desktop_notification_title(&msg) {
+                    notifier.send(title, notification_body(app, &msg));
                 }
+            }
+            
+            if Some(&msg.channel_id) == app.selected_channel.as_ref() {
+                load_message_images(app, std::slice::from_ref(&msg)).await;
                 
                 app.messages.push(msg.clone());
             }
@@ -143,6 +387,65 @@ async fn handle_discord_event(app: &mut App, event: DiscordEvent) {
                 messages.push(msg);
             }
         }
+        DiscordEvent::TypingStarted(channel_id, user_id, name) => {
+            let name = name
+                .or_else(|| {
+                    app.messages
+                        .iter()
+                        .rev()
+                        .find(|m| m.author_id == user_id)
+                        .map(|m| m.author.clone())
+                })
+                .unwrap_or_else(|| "Someone".to_string());
+            app.start_typing(&channel_id, &user_id, name);
+        }
+        DiscordEvent::MessageEdited(channel_id, message_id, content) => {
+            app.apply_message_edit(&channel_id, &message_id, &content);
+        }
+        DiscordEvent::EmbedsUpdated(channel_id, message_id, embeds) => {
+            app.update_message(&channel_id, &message_id, |msg| msg.embeds = embeds.clone());
+            
+            let updated = app.messages.iter().find(|m| m.id == message_id).cloned();
+            if let Some(msg) = updated {
+                load_message_images(app, std::slice::from_ref(&msg)).await;
+            }
+        }
+        DiscordEvent::PinsUpdated(channel_id) => {
+            // Refresh the open channel so its pin flags stay right, and forget the rest
+            if app.selected_channel.as_deref() == Some(channel_id.as_str()) {
+                app.loading_pins = true;
+            } else {
+                app.pins.remove(&channel_id);
+            }
+        }
+        DiscordEvent::MessageDeleted(channel_id, message_id) => {
+            app.remove_message(&channel_id, &message_id);
+        }
+        DiscordEvent::ThreadUpdated(guild_id, thread) => {
+            app.upsert_thread(&guild_id, thread);
+        }
+        DiscordEvent::ThreadDeleted(guild_id, thread_id) => {
+            app.remove_thread(&guild_id, &thread_id);
+        }
+        DiscordEvent::ReactionAdded(channel_id, message_id, emoji, user_id) => {
+            let by_me = app.current_user_id.as_deref() == Some(user_id.as_str());
+            if let Some(id) = emoji.id.as_deref() {
+                if app.config.images.enabled && app.config.images.render_emojis && app.image_renderer.is_supported() {
+                    let _ = app.image_renderer.load_emoji(id).await;
+                }
+            }
+            app.update_message(&channel_id, &message_id, |msg| msg.add_reaction(emoji.clone(), by_me));
+        }
+        DiscordEvent::ReactionRemoved(channel_id, message_id, emoji, user_id) => {
+            let by_me = app.current_user_id.as_deref() == Some(user_id.as_str());
+            app.update_message(&channel_id, &message_id, |msg| msg.remove_reaction(&emoji, by_me));
+        }
+        DiscordEvent::ReactionsCleared(channel_id, message_id, emoji) => {
+            app.update_message(&channel_id, &message_id, |msg| match &emoji {
+                Some(emoji) => msg.reactions.retain(|r| !r.emoji.same_as(emoji)),
+                None => msg.reactions.clear(),
+            });
+        }
         _ => {}
     }
 }
@@ -155,8 +458,15 @@ async fn run_app(
     let mut cache_stats_timer = tokio::time::Instant::now();
     let cache_check_interval = std::time::Duration::from_secs(10);
     let cache_stats_interval = std::time::Duration::from_secs(5);
+    let mut archive_timer = tokio::time::Instant::now();
+    let archive_interval = std::time::Duration::from_secs(30);
     
     loop {
+        let switch_to = app.lock().await.switching_account.take();
+        if let Some(account) = switch_to {
+            switch_account(&app, account).await;
+        }
+        
         {
             let mut app = app.lock().await;
             
@@ -173,7 +483,19 @@ async fn run_app(
                 cache_stats_timer = tokio::time::Instant::now();
             }
             
+            if archive_timer.elapsed() >= archive_interval {
+                save_archive(&mut app).await;
+                archive_timer = tokio::time::Instant::now();
+            }
+            
             app.clear_expired_notifications();
+            app.clear_expired_typing();
+            
+            if app.offline && (app.loading_dms || app.loading_channels) {
+                app.loading_dms = false;
+                app.loading_channels = false;
+                app.add_notification(Notification::info("Offline: only archived channels are available"));
+            }
             
             if app.loading_dms {
                 let client_arc = app.discord_client.clone();
@@ -213,6 +535,12 @@ async fn run_app(
                                 drop(client);
                                 
                                 app.channel_cache.insert(guild_id.clone(), channel_list);
+                                
+                                let client = client_arc.lock().await;
+                                if let Ok(roles) = client.fetch_roles(&guild_id).await {
+                                    app.role_names.extend(roles);
+                                }
+                                drop(client);
 
                                 if let Some(guild) = app.guilds.iter().find(|g| g.id == guild_id) {
                                     let guild_name = guild.name.clone();
@@ -247,49 +575,27 @@ async fn run_app(
             if app.loading_messages {
                 if let Some(channel_id) = &app.selected_channel.clone() {
                     if !app.message_cache.contains_key(channel_id) {
+                        load_archived_messages(&mut app, channel_id).await;
+                    }
+                    if !app.message_cache.contains_key(channel_id) && !app.offline {
                         let client_arc = app.discord_client.clone();
                         if let Some(client_arc) = client_arc {
                             let client = client_arc.lock().await;
                             
-                            match client.fetch_messages(channel_id, 50).await {
+                            match client.fetch_messages(channel_id, MESSAGE_PAGE_SIZE, None).await {
                                 Ok(messages) => {
                                     drop(client);
                                     
                                     app.messages = messages.clone();
+                                    load_message_images(&mut app, &messages).await;
+                                    resolve_mentions(&mut app, &messages).await;
                                     
-                                    let min = (
-                                        app.config.images.min_image_width as u32,
-                                        app.config.images.min_image_height as u32,
-                                    );
-                                    let max = (
-                                        app.config.images.max_image_width as u32,
-                                        app.config.images.max_image_height as u32,
-                                    );
-                                    
-                                    if app.config.images.enabled && app.config.images.render_avatars {
-                                        for msg in &messages {
-                                            let user_id = &msg.author_id;
-                                            let avatar_hash = msg.author_avatar.as_deref();
-                                            let _ = app.image_renderer.load_avatar(user_id, avatar_hash).await;
-                                        }
-                                    }
-                                    
-                                    if app.config.images.enabled && app.config.images.render_attachments {
-                                        for msg in &messages {
-                                            for attachment in msg.attachments.iter().filter(|a| a.is_image()) {
-                                                let _ = app.image_renderer
-                                                    .load_attachment(
-                                                        &attachment.id, 
-                                                        &attachment.url,
-                                                        min,
-                                                        max,
-                                                    )
-                                                    .await;
-                                            }
-                                        }
+                                    if messages.len() < MESSAGE_PAGE_SIZE as usize {
+                                        app.history_exhausted.insert(channel_id.clone());
                                     }
                                     
                                     app.message_cache.insert(channel_id.clone(), messages);
+                                    app.mark_channel_read(channel_id);
                                 }
                                 Err(e) => {
                                     drop(client);
@@ -301,14 +607,78 @@ async fn run_app(
                         }
                     }
                     app.loading_messages = false;
+                    continue_jump(&mut app);
+                }
+            }
+
+            if app.backfill_pending {
+                app.backfill_pending = false;
+                backfill_missed_messages(&mut app).await;
+            }
+
+            if app.loading_forum_posts {
+                if let Some(forum_id) = app.selected_forum.clone() {
+                    let guild_id = app.guild_id_for_channel(&forum_id);
+                    let client_arc = app.discord_client.clone();
+                    if let (Some(guild_id), Some(client_arc)) = (guild_id, client_arc) {
+                        let client = client_arc.lock().await;
+                        let result = client.fetch_forum_posts(&guild_id, &forum_id).await;
+                        drop(client);
+
+                        match result {
+                            Ok(posts) => {
+                                app.forum_posts.insert(forum_id, posts);
+                            }
+                            Err(e) => {
+                                app.add_notification(Notification::error(
+                                    format!("Failed to load forum posts: {}", e)
+                                ));
+                            }
+                        }
+                    }
                 }
+                app.loading_forum_posts = false;
             }
 
+            if app.search.as_ref().is_some_and(|s| s.loading) {
+                run_search(&mut app).await;
+            }
+
+            if app.loading_pins {
+                app.loading_pins = false;
+                load_pins(&mut app).await;
+            }
+
+            if let Some(account) = app.removing_token.take() {
+                remove_token(&mut app, &account).await;
+            }
+
+            process_pending_actions(&mut app).await;
+            flush_outbox(&mut app).await;
+
             terminal.draw(|f| {
                 ui::draw(f, &mut app);
                 // Draw notifications on top
                 ui::notifications::draw(f, &app, f.area());
             })?;
+
+            // Checked after drawing so the archived messages are on screen meanwhile
+            if let Some(channel_id) = app.reconcile_pending.take() {
+                reconcile_channel(&mut app, &channel_id).await;
+            }
+
+            // Fetched after drawing so the loading indicator is visible while we wait
+            if app.loading_older_messages {
+                load_older_messages(&mut app).await;
+                app.loading_older_messages = false;
+                continue_jump(&mut app);
+            }
+        }
+        
+        // Checked after drawing so the token screen shows the check is under way
+        let submitted = app.lock().await.token_setup.clone().filter(|setup| setup.validating);
+        if let Some(setup) = submitted {
+            submit_token(&app, setup).await;
         }
 
         if event::poll(std::time::Duration::from_millis(100))? {
@@ -323,57 +693,837 @@ async fn run_app(
                 };
                 
                 if should_send {
-                    let channel_id = app_lock.selected_channel.clone();
-                    let content = app_lock.input.clone();
-                    let file_paths: Vec<String> = app_lock.attached_files
-                        .iter()
-                        .map(|f| f.path.clone())
-                        .collect();
-                    
-                    let has_files = !file_paths.is_empty();
-                    let has_content = !content.is_empty();
-                    
-                    if has_content || has_files {
-                        if let Some(channel_id) = channel_id {
-                            let client_arc = app_lock.discord_client.clone();
-                            if let Some(client_arc) = client_arc {
-                                let client = client_arc.lock().await;
-                                
-                                let result = if has_files {
-                                    client.send_message_with_files(&channel_id, &content, &file_paths).await
-                                } else {
-                                    client.send_message(&channel_id, &content).await
-                                };
-                                
-                                match result {
-                                    Ok(_) => {
-                                        drop(client);
-                                        if has_files {
-                                            app_lock.add_notification(Notification::success(
-                                                format!("Sent message with {} file(s)", file_paths.len())
-                                            ));
-                                        }
-                                    }
-                                    Err(e) => {
-                                        drop(client);
-                                        app_lock.add_notification(Notification::error(
-                                            format!("Failed to send message: {}", e)
-                                        ));
-                                    }
-                                }
-                            }
-                        }
-                        
-                        app_lock.input.clear();
-                        app_lock.input_cursor = 0;
-                        app_lock.attached_files.clear();
+                    if let Some(message_id) = app_lock.editing_message.take() {
+                        edit_input(&mut app_lock, &message_id).await;
+                    } else {
+                        send_input(&mut app_lock);
                     }
                 }
                 
                 if handle_input(&mut app_lock, key, terminal)? {
+                    save_archive(&mut app_lock).await;
                     return Ok(());
                 }
             }
         }
     }
 }
+
+/// Move the typed message into the outbox. It is sent by `flush_outbox`.
+fn send_input(app: &mut App) {
+    let Some(channel_id) = app.selected_channel.clone() else {
+        return;
+    };
+    let content = app.input.clone();
+    let file_paths: Vec<String> = app.attached_files
+        .iter()
+        .map(|f| f.path.clone())
+        .collect();
+    
+    if content.is_empty() && file_paths.is_empty() {
+        return;
+    }
+    
+    let outgoing = OutgoingMessage::new(snowflake::generate(), channel_id, content)
+        .with_files(file_paths)
+        .with_reply_to(app.replying_to.take());
+    app.queue_message(outgoing);
+    
+    app.input.clear();
+    app.input_cursor = 0;
+    app.attached_files.clear();
+    // Discord clears our typing state once the message lands
+    app.last_typing_sent = None;
+}
+
+/// Start sending queued messages. They wait while the gateway is down so a
+/// reconnect does not turn them into failures.
+async fn flush_outbox(app: &mut App) {
+    if !app.connection.is_ready() || !app.outbox.iter().any(|o| o.state == SendState::Queued) {
+        return;
+    }
+    let Some(client_arc) = app.discord_client.clone() else {
+        return;
+    };
+    let client = client_arc.lock().await;
+    
+    let queued: Vec<OutgoingMessage> = app.outbox
+        .iter()
+        .filter(|o| o.state == SendState::Queued)
+        .cloned()
+        .collect();
+    for outgoing in queued {
+        app.set_send_state(&outgoing.channel_id, &outgoing.nonce, SendState::Sending);
+        client.spawn_send(outgoing);
+    }
+}
+
+async fn edit_input(app: &mut App, message_id: &str) {
+    let channel_id = app.selected_channel.clone();
+    let content = app.input.clone();
+
+    if app.offline {
+        // Keep editing so the change is not lost
+        app.editing_message = Some(message_id.to_string());
+        app.add_notification(Notification::warning("Offline: the archive is read-only"));
+        return;
+    }
+    if let Some(channel_id) = channel_id.filter(|_| !content.is_empty()) {
+        let client_arc = app.discord_client.clone();
+        if let Some(client_arc) = client_arc {
+            let client = client_arc.lock().await;
+            let result = client.edit_message(&channel_id, message_id, &content).await;
+            drop(client);
+
+            match result {
+                Ok(msg) => {
+                    app.apply_message_edit(&channel_id, &msg.id, &msg.content);
+                }
+                Err(e) => {
+                    app.add_notification(Notification::error(
+                        format!("Failed to edit message: {}", e)
+                    ));
+                }
+            }
+        }
+    }
+
+    app.input.clear();
+    app.input_cursor = 0;
+}
+
+async fn load_message_images(app: &mut App, messages: &[Message]) {
+    let min = (
+        app.config.images.min_image_width as u32,
+        app.config.images.min_image_height as u32,
+    );
+    let max = (
+        app.config.images.max_image_width as u32,
+        app.config.images.max_image_height as u32,
+    );
+    
+    if app.config.images.enabled && app.config.images.render_avatars {
+        for msg in messages {
+            let user_id = &msg.author_id;
+            let avatar_hash = msg.author_avatar.as_deref();
+            let _ = app.image_renderer.load_avatar(user_id, avatar_hash).await;
+        }
+    }
+    
+    if app.config.images.enabled && app.config.images.render_attachments {
+        // Thumbnails sit beside the text in Discord, so they get half the space
+        let thumbnail_max = ((max.0 / 2).max(min.0), (max.1 / 2).max(min.1));
+        
+        for msg in messages {
+            for (i, embed) in msg.embeds.iter().enumerate() {
+                if let Some(thumbnail) = &embed.thumbnail {
+                    let key = Embed::thumbnail_key(&msg.id, i);
+                    let _ = app.image_renderer.load_attachment(&key, &thumbnail.url, min, thumbnail_max).await;
+                }
+                if let Some(image) = &embed.image {
+                    let key = Embed::image_key(&msg.id, i);
+                    let _ = app.image_renderer.load_attachment(&key, &image.url, min, max).await;
+                }
+            }
+        }
+    }
+    
+    if app.config.images.enabled && app.config.images.render_stickers {
+        for msg in messages {
+            for sticker in &msg.stickers {
+                if let Some(extension) = sticker.image_extension() {
+                    let url = app.image_renderer.get_sticker_url(&sticker.id, extension);
+                    let _ = app.image_renderer
+                        .load_attachment(&sticker.cache_key(), &url, min, max)
+                        .await;
+                }
+            }
+        }
+    }
+    
+    if app.config.images.enabled && app.config.images.render_emojis && app.image_renderer.is_supported() {
+        for msg in messages {
+            for mention in find_mentions(&msg.content) {
+                if let Mention::Emoji(emoji) = mention {
+                    if let Some(id) = emoji.id.as_deref() {
+                        let _ = app.image_renderer.load_emoji(id).await;
+                    }
+                }
+            }
+            for reaction in &msg.reactions {
+                if let Some(id) = reaction.emoji.id.as_deref() {
+                    let _ = app.image_renderer.load_emoji(id).await;
+                }
+            }
+        }
+    }
+    
+    if app.config.images.enabled && app.config.images.render_attachments {
+        for msg in messages {
+            for attachment in msg.attachments.iter().filter(|a| a.is_image()) {
+                let _ = app.image_renderer
+                    .load_attachment(
+                        &attachment.id, 
+                        &attachment.url,
+                        min,
+                        max,
+                    )
+                    .await;
+            }
+        }
+    }
+}
+
+/// Learn names from the messages and look up any mentioned users or channels we don't know
+async fn resolve_mentions(app: &mut App, messages: &[Message]) {
+    app.remember_names(messages);
+    
+    let mut unknown = Vec::new();
+    for msg in messages {
+        for mention in find_mentions(&msg.content) {
+            let known = match &mention {
+                Mention::User(id) => app.user_name(id).is_some() || app.failed_lookups.contains(id),
+                Mention::Channel(id) => app.channel_name(id).is_some() || app.failed_lookups.contains(id),
+                _ => true,
+            };
+            if !known && !unknown.contains(&mention) {
+                unknown.push(mention);
+            }
+        }
+    }
+    
+    if unknown.is_empty() || app.offline {
+        return;
+    }
+    let Some(client_arc) = app.discord_client.clone() else {
+        return;
+    };
+    let client = client_arc.lock().await;
+    
+    for mention in unknown {
+        match mention {
+            // Only give up on IDs Discord says are gone or hidden; anything else is tried again later
+            Mention::User(id) => match client.fetch_user_name(&id).await {
+                Ok(name) => {
+                    app.user_names.insert(id, name);
+                }
+                Err(e) if discord::is_not_found(&e) => {
+                    app.failed_lookups.insert(id);
+                }
+                Err(_) => {}
+            },
+            Mention::Channel(id) => match client.fetch_channel_name(&id).await {
+                Ok(name) => {
+                    app.channel_names.insert(id, name);
+                }
+                Err(e) if discord::is_not_found(&e) => {
+                    app.failed_lookups.insert(id);
+                }
+                Err(_) => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Fetch our roles in guilds where the messages mention a role, so those mentions can count as ours
+async fn load_member_roles(app: &mut App, messages: &[Message]) {
+    let mut guild_ids: Vec<String> = messages
+        .iter()
+        .filter(|m| !m.mention_roles.is_empty())
+        .filter_map(|m| m.guild_id.clone().or_else(|| app.guild_id_for_channel(&m.channel_id)))
+        .filter(|guild_id| !app.member_roles.contains_key(guild_id))
+        .collect();
+    guild_ids.sort();
+    guild_ids.dedup();
+    
+    if guild_ids.is_empty() || app.offline {
+        return;
+    }
+    let (Some(client_arc), Some(user_id)) = (app.discord_client.clone(), app.current_user_id.clone()) else {
+        return;
+    };
+    let client = client_arc.lock().await;
+    
+    for guild_id in guild_ids {
+        // Failed lookups are tried again with the next role mention
+        if let Ok(roles) = client.fetch_member_roles(&guild_id, &user_id).await {
+            app.member_roles.insert(guild_id, roles);
+        }
+    }
+}
+
+/// Fetch what was sent to cached channels while the gateway was down
+async fn backfill_missed_messages(app: &mut App) {
+    let Some(client_arc) = app.discord_client.clone() else {
+        return;
+    };
+    
+    let channels: Vec<(String, String)> = app.message_cache
+        .iter()
+        .filter_map(|(channel_id, messages)| {
+            // Placeholders of queued or failed sends are newer than anything we missed
+            messages.iter().rev().find(|m| m.is_sent()).map(|m| (channel_id.clone(), m.id.clone()))
+        })
+        .collect();
+    
+    let mut missed_count = 0;
+    let mut failed = 0;
+    
+    for (channel_id, mut after) in channels {
+        let mut gap_left = false;
+        for _ in 0..MAX_BACKFILL_PAGES {
+            let result = {
+                let client = client_arc.lock().await;
+                client.fetch_messages_after(&channel_id, MESSAGE_PAGE_SIZE, &after).await
+            };
+            let page = match result {
+                Ok(page) => page,
+                Err(_) => {
+                    failed += 1;
+                    break;
+                }
+            };
+            let Some(newest) = page.last() else {
+                break;
+            };
+            after = newest.id.clone();
+            gap_left = page.len() >= MESSAGE_PAGE_SIZE as usize;
+            
+            resolve_mentions(app, &page).await;
+            load_member_roles(app, &page).await;
+            let missed = app.append_missed_messages(&channel_id, page);
+            if app.selected_channel.as_deref() == Some(channel_id.as_str()) {
+                load_message_images(app, &missed).await;
+            }
+            for msg in &missed {
+                app.track_unread(msg);
+            }
+            missed_count += missed.len();
+            
+            if !gap_left {
+                break;
+            }
+        }
+        
+        // Too much was missed to page through; like reconcile_channel, start the channel over
+        if gap_left {
+            let missed = restart_channel(app, &channel_id).await;
+            resolve_mentions(app, &missed).await;
+            load_member_roles(app, &missed).await;
+            if app.selected_channel.as_deref() == Some(channel_id.as_str()) {
+                load_message_images(app, &missed).await;
+            }
+            for msg in &missed {
+                app.track_unread(msg);
+            }
+            missed_count += missed.len();
+        }
+    }
+    
+    if missed_count > 0 {
+        app.add_notification(Notification::info(format!("Caught up on {} missed messages", missed_count)));
+    }
+    if failed > 0 {
+        app.add_notification(Notification::warning(format!("Could not catch up on {} channels", failed)));
+    }
+}
+
+/// Replace a channel's history with its newest page so no hole is left in the cache or the
+/// archive, returning the messages that were not loaded before
+async fn restart_channel(app: &mut App, channel_id: &str) -> Vec<Message> {
+    let Some(client_arc) = app.discord_client.clone() else {
+        return Vec::new();
+    };
+    let result = {
+        let client = client_arc.lock().await;
+        client.fetch_messages(channel_id, MESSAGE_PAGE_SIZE, None).await
+    };
+    if let Some(archive) = app.archive.as_mut() {
+        let _ = archive.clear_channel(channel_id).await;
+    }
+    
+    let (shown, unsent): (Vec<Message>, Vec<Message>) = app.message_cache
+        .remove(channel_id)
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|m| m.is_sent());
+    // Without the newest page the channel is loaded again when it is next opened
+    let Ok(newest) = result else {
+        return Vec::new();
+    };
+    
+    let known: std::collections::HashSet<String> = shown.iter().map(|m| m.id.clone()).collect();
+    let fresh: Vec<Message> = newest.iter().filter(|m| !known.contains(&m.id)).cloned().collect();
+    if newest.len() < MESSAGE_PAGE_SIZE as usize {
+        app.history_exhausted.insert(channel_id.to_string());
+    } else {
+        app.history_exhausted.remove(channel_id);
+    }
+    
+    let mut messages = newest;
+    messages.extend(unsent);
+    if app.selected_channel.as_deref() == Some(channel_id) {
+        let selected = app.selected_message().map(|m| m.id.clone());
+        app.messages = messages.clone();
+        if !selected.is_some_and(|id| app.scroll_to_message(&id)) {
+            app.message_scroll = app.message_scroll.min(app.messages.len().saturating_sub(1));
+        }
+    }
+    app.message_cache.insert(channel_id.to_string(), messages);
+    fresh
+}
+
+/// Plain text shown in a desktop notification for a message
+fn notification_body(app: &App, msg: &Message) -> String {
+    if !msg.content.is_empty() {
+        return ui::mentions::resolve_plain(app, &msg.content);
+    }
+    if let Some(sticker) = msg.stickers.first() {
+        return format!("Sent a sticker: {}", sticker.name);
+    }
+    if !msg.attachments.is_empty() {
+        return "Sent an attachment".to_string();
+    }
+    String::new()
+}
+
+async fn load_older_messages(app: &mut App) {
+    let Some(channel_id) = app.selected_channel.clone() else {
+        return;
+    };
+    let Some(oldest_id) = app.messages.first().map(|m| m.id.clone()) else {
+        return;
+    };
+    
+    // The archive often reaches further back than what is loaded, without a round trip
+    let mut older = match &app.archive {
+        Some(archive) => archive
+            .load_before(&channel_id, &oldest_id, MESSAGE_PAGE_SIZE as usize)
+            .await
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    
+    if older.len() < MESSAGE_PAGE_SIZE as usize && !app.offline {
+        let Some(client_arc) = app.discord_client.clone() else {
+            return;
+        };
+        let before = older.first().map(|m| m.id.clone()).unwrap_or(oldest_id);
+        let limit = MESSAGE_PAGE_SIZE - older.len() as u8;
+        
+        let client = client_arc.lock().await;
+        let result = client.fetch_messages(&channel_id, limit, Some(&before)).await;
+        drop(client);
+        
+        match result {
+            Ok(mut fetched) => {
+                if fetched.len() < limit as usize {
+                    app.history_exhausted.insert(channel_id.clone());
+                }
+                fetched.append(&mut older);
+                older = fetched;
+            }
+            Err(e) => {
+                app.jump_target = None;
+                app.add_notification(Notification::error(
+                    format!("Failed to load older messages: {}", e)
+                ));
+            }
+        }
+    }
+    
+    if older.is_empty() {
+        if app.offline {
+            app.jump_target = None;
+            app.add_notification(Notification::info("Offline: no older archived messages"));
+        }
+        return;
+    }
+    load_message_images(app, &older).await;
+    resolve_mentions(app, &older).await;
+    app.prepend_messages(&channel_id, older);
+}
+
+/// Show a channel's archived messages straight away. They are checked against Discord
+/// once they are on screen, or left as they are while offline.
+async fn load_archived_messages(app: &mut App, channel_id: &str) {
+    let Some(archive) = &app.archive else {
+        return;
+    };
+    let archived = match archive.load_recent(channel_id, MESSAGE_PAGE_SIZE as usize).await {
+        Ok(archived) => archived,
+        Err(e) => {
+            app.add_notification(Notification::warning(format!("Could not read archived messages: {}", e)));
+            Vec::new()
+        }
+    };
+    if archived.is_empty() {
+        if app.offline {
+            app.add_notification(Notification::info("Offline: no archived messages for this channel"));
+        }
+        return;
+    }
+    
+    app.messages = archived.clone();
+    load_message_images(app, &archived).await;
+    resolve_mentions(app, &archived).await;
+    app.message_cache.insert(channel_id.to_string(), archived);
+    app.mark_channel_read(channel_id);
+    if !app.offline {
+        app.reconcile_pending = Some(channel_id.to_string());
+    }
+}
+
+/// Bring archived messages shown for a channel up to date: fill in what was sent since they
+/// were archived, and take edits and deletions from the newest page
+async fn reconcile_channel(app: &mut App, channel_id: &str) {
+    let Some(client_arc) = app.discord_client.clone() else {
+        return;
+    };
+    let client = client_arc.lock().await;
+    
+    let newest = match client.fetch_messages(channel_id, MESSAGE_PAGE_SIZE, None).await {
+        Ok(newest) => newest,
+        Err(e) => {
+            app.add_notification(Notification::warning(
+                format!("Showing archived messages, could not refresh: {}", e)
+            ));
+            return;
+        }
+    };
+    
+    let (shown, unsent): (Vec<Message>, Vec<Message>) = app.message_cache
+        .get(channel_id)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|m| m.send_state.is_none());
+    let known: std::collections::HashSet<String> = shown.iter().map(|m| m.id.clone()).collect();
+    
+    let complete = newest.len() < MESSAGE_PAGE_SIZE as usize;
+    let newest_shown = shown.last().map(|m| m.id.clone());
+    let overlaps = match (&newest_shown, newest.first()) {
+        (Some(shown), Some(first)) => snowflake::value(shown) >= snowflake::value(&first.id),
+        _ => false,
+    };
+    
+    let recent = if complete || overlaps {
+        Some(newest.clone())
+    } else {
+        match newest_shown {
+            Some(after) => fetch_gap(&**client, channel_id, &after, &newest).await,
+            None => None,
+        }
+    };
+    drop(client);
+    
+    let mut messages = match recent.filter(|_| !complete) {
+        Some(recent) => archive::merge_history(shown, recent),
+        None => {
+            // Too much happened since, or the whole channel fits in one page; start the archive over
+            if complete {
+                app.history_exhausted.insert(channel_id.to_string());
+            }
+            if let Some(archive) = app.archive.as_mut() {
+                let _ = archive.clear_channel(channel_id).await;
+            }
+            newest
+        }
+    };
+    messages.extend(unsent);
+    
+    let fresh: Vec<Message> = messages.iter().filter(|m| !known.contains(&m.id)).cloned().collect();
+    load_message_images(app, &fresh).await;
+    resolve_mentions(app, &fresh).await;
+    
+    if app.selected_channel.as_deref() == Some(channel_id) {
+        let selected = app.selected_message().map(|m| m.id.clone());
+        app.messages = messages.clone();
+        if !selected.is_some_and(|id| app.scroll_to_message(&id)) {
+            app.message_scroll = app.message_scroll.min(app.messages.len().saturating_sub(1));
+        }
+    }
+    app.message_cache.insert(channel_id.to_string(), messages);
+    app.mark_channel_read(channel_id);
+}
+
+/// Messages after `after` up to the start of `newest`, joined onto `newest`.
+/// `None` when the gap is too long to fetch or fetching fails.
+async fn fetch_gap(client: &dyn ChatBackend, channel_id: &str, after: &str, newest: &[Message]) -> Option<Vec<Message>> {
+    let target = newest.first().map(|m| snowflake::value(&m.id))?;
+    let mut gap = Vec::new();
+    let mut after = after.to_string();
+    
+    for _ in 0..MAX_BACKFILL_PAGES {
+        let page = client.fetch_messages_after(channel_id, MESSAGE_PAGE_SIZE, &after).await.ok()?;
+        let reached = page.len() < MESSAGE_PAGE_SIZE as usize
+            || page.last().is_some_and(|m| snowflake::value(&m.id) >= target);
+        after = page.last()?.id.clone();
+        gap.extend(page.into_iter().filter(|m| snowflake::value(&m.id) < target));
+        
+        if reached {
+            gap.extend(newest.iter().cloned());
+            return Some(gap);
+        }
+    }
+    None
+}
+
+/// Write every loaded channel and the sidebar to the archive, skipping what has not changed
+async fn save_archive(app: &mut App) {
+    let session = app.archived_session();
+    let Some(archive) = app.archive.as_mut() else {
+        return;
+    };
+    
+    let mut result = archive.save_session(&session).await;
+    for (channel_id, messages) in &app.message_cache {
+        result = result.and(archive.save_channel(channel_id, messages).await);
+    }
+    if let Err(e) = result {
+        app.add_notification(Notification::warning(format!("Could not save message archive: {}", e)));
+    }
+}
+
+/// Browse the archive read-only, with the sidebar as it was last time
+async fn go_offline(app: &mut App) {
+    app.offline = true;
+    
+    let session = match &app.archive {
+        Some(archive) => archive.load_session().await,
+        None => Ok(None),
+    };
+    match session {
+        Ok(Some(session)) => app.restore_session(session),
+        Ok(None) => {}
+        Err(e) => app.add_notification(Notification::warning(
+            format!("Could not read archived session: {}", e)
+        )),
+    }
+    app.add_notification(Notification::warning("Discord is unreachable, showing archived messages read-only"));
+}
+
+/// Keep paging back through history until the message we are jumping to turns up
+fn continue_jump(app: &mut App) {
+    let Some(target) = app.jump_target.clone() else {
+        return;
+    };
+    let exhausted = app.selected_channel
+        .as_deref()
+        .is_none_or(|id| app.is_history_exhausted(id));
+
+    if app.scroll_to_message(&target) {
+        app.jump_target = None;
+    } else if exhausted {
+        app.jump_target = None;
+        app.add_notification(Notification::warning("Message was not found in the channel history"));
+    } else {
+        app.loading_older_messages = true;
+    }
+}
+
+/// Fetch the page of search results the search overlay is waiting on
+async fn run_search(app: &mut App) {
+    let Some(search) = app.search.as_mut() else {
+        return;
+    };
+    search.loading = false;
+    let Some(query) = search.query.clone() else {
+        return;
+    };
+    let (scope, offset) = (search.scope.clone(), search.offset());
+    let Some(client_arc) = app.discord_client.clone() else {
+        return;
+    };
+
+    let client = client_arc.lock().await;
+    let result = client.search_messages(&scope, &query, offset).await;
+
+    // Hits can come from threads that are not in the sidebar
+    if let Ok(page) = &result {
+        for msg in &page.messages {
+            if app.channel_name(&msg.channel_id).is_none() && !app.failed_lookups.contains(&msg.channel_id) {
+                match client.fetch_channel_name(&msg.channel_id).await {
+                    Ok(name) => {
+                        app.channel_names.insert(msg.channel_id.clone(), name);
+                    }
+                    Err(_) => {
+                        app.failed_lookups.insert(msg.channel_id.clone());
+                    }
+                }
+            }
+        }
+    }
+    drop(client);
+
+    match result {
+        Ok(page) => {
+            resolve_mentions(app, &page.messages).await;
+            if let Some(search) = app.search.as_mut() {
+                search.total_results = page.total_results;
+                search.results = page.messages;
+                search.selected_idx = 0;
+            }
+        }
+        Err(e) => {
+            app.add_notification(Notification::error(format!("Search failed: {}", e)));
+        }
+    }
+}
+
+/// Fetch the open channel's pins
+async fn load_pins(app: &mut App) {
+    let Some(channel_id) = app.selected_channel.clone() else {
+        return;
+    };
+    let Some(client_arc) = app.discord_client.clone() else {
+        return;
+    };
+
+    let client = client_arc.lock().await;
+    let result = client.fetch_pins(&channel_id).await;
+    drop(client);
+
+    match result {
+        Ok(pins) => {
+            load_message_images(app, &pins).await;
+            resolve_mentions(app, &pins).await;
+            app.set_pins(&channel_id, pins);
+        }
+        Err(e) => {
+            app.add_notification(Notification::error(
+                format!("Failed to load pinned messages: {}", e)
+            ));
+        }
+    }
+}
+
+/// Whether we may pin in a channel, asking Discord the first time a pin or unpin is tried.
+/// The answer takes a few requests, so it is not worked out just to show the pins list.
+async fn check_pin_permission(app: &mut App, client: &dyn ChatBackend, channel_id: &str) -> bool {
+    if !app.pin_permissions.contains_key(channel_id) {
+        let Some(user_id) = app.current_user_id.clone() else {
+            return true;
+        };
+        let guild_id = app.guild_id_for_channel(channel_id);
+        // Leave the permission unknown on failure and let Discord decide
+        if let Ok(allowed) = client.can_manage_messages(guild_id.as_deref(), channel_id, &user_id).await {
+            app.pin_permissions.insert(channel_id.to_string(), allowed);
+        }
+    }
+
+    let allowed = app.can_pin(channel_id);
+    if !allowed {
+        app.add_notification(Notification::info("You do not have permission to pin messages here"));
+    }
+    allowed
+}
+
+async fn process_pending_actions(app: &mut App) {
+    let actions: Vec<PendingAction> = app.pending_actions.drain(..).collect();
+    if app.offline && !actions.is_empty() {
+        app.add_notification(Notification::warning("Offline: the archive is read-only"));
+        return;
+    }
+
+    for action in actions {
+        let Some(client_arc) = app.discord_client.clone() else {
+            continue;
+        };
+        let client = client_arc.lock().await;
+
+        match action {
+            PendingAction::DeleteMessage { channel_id, message_id } => {
+                let result = client.delete_message(&channel_id, &message_id).await;
+                drop(client);
+
+                match result {
+                    Ok(()) => app.remove_message(&channel_id, &message_id),
+                    Err(e) => app.add_notification(Notification::error(
+                        format!("Failed to delete message: {}", e)
+                    )),
+                }
+            }
+            PendingAction::AddReaction { channel_id, message_id, emoji } => {
+                let result = client.add_reaction(&channel_id, &message_id, &emoji).await;
+                drop(client);
+
+                match result {
+                    Ok(()) => app.update_message(&channel_id, &message_id, |msg| {
+                        msg.add_reaction(emoji.clone(), true)
+                    }),
+                    Err(e) => app.add_notification(Notification::error(
+                        format!("Failed to add reaction: {}", e)
+                    )),
+                }
+            }
+            PendingAction::RemoveReaction { channel_id, message_id, emoji } => {
+                let result = client.remove_reaction(&channel_id, &message_id, &emoji).await;
+                drop(client);
+
+                match result {
+                    Ok(()) => app.update_message(&channel_id, &message_id, |msg| {
+                        msg.remove_reaction(&emoji, true)
+                    }),
+                    Err(e) => app.add_notification(Notification::error(
+                        format!("Failed to remove reaction: {}", e)
+                    )),
+                }
+            }
+            PendingAction::PinMessage { channel_id, message_id } => {
+                if !check_pin_permission(app, &**client, &channel_id).await {
+                    continue;
+                }
+                let result = client.pin_message(&channel_id, &message_id).await;
+                drop(client);
+
+                match result {
+                    Ok(()) => {
+                        app.set_pinned(&channel_id, &message_id, true);
+                        app.add_notification(Notification::success("Pinned message"));
+                    }
+                    Err(e) => app.add_notification(Notification::error(
+                        format!("Failed to pin message: {}", e)
+                    )),
+                }
+            }
+            PendingAction::UnpinMessage { channel_id, message_id } => {
+                if !check_pin_permission(app, &**client, &channel_id).await {
+                    continue;
+                }
+                let result = client.unpin_message(&channel_id, &message_id).await;
+                drop(client);
+
+                match result {
+                    Ok(()) => {
+                        app.set_pinned(&channel_id, &message_id, false);
+                        app.add_notification(Notification::success("Unpinned message"));
+                    }
+                    Err(e) => app.add_notification(Notification::error(
+                        format!("Failed to unpin message: {}", e)
+                    )),
+                }
+            }
+            PendingAction::TriggerTyping { channel_id } => {
+                // Typing indicators are best-effort, so failures are ignored
+                let _ = client.trigger_typing(&channel_id).await;
+            }
+            PendingAction::CreateForumPost { forum_id, title, content, tag_ids } => {
+                let result = client.create_forum_post(&forum_id, &title, &content, &tag_ids).await;
+                drop(client);
+
+                match result {
+                    Ok(post) => {
+                        app.forum_posts.entry(forum_id).or_default().insert(0, post);
+                        app.forum_selected_idx = 0;
+                        app.add_notification(Notification::success(format!("Created post {}", title)));
+                    }
+                    Err(e) => app.add_notification(Notification::error(
+                        format!("Failed to create post: {}", e)
+                    )),
+                }
+            }
+        }
+    }
+}
@@ -1,26 +1,35 @@
-#[derive(Debug, Clone)]
+use super::forum::ForumTag;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Channel {
     pub id: String,
     pub name: String,
     pub kind: ChannelType,
     pub position: i32,
     pub parent_id: Option<String>,
+    /// Tags that can be applied to posts in a forum channel
+    pub tags: Vec<ForumTag>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ChannelCategory {
     pub id: String,
     pub name: String,
     pub position: i32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChannelType {
+    #[default]
     Text,
     Voice,
     Announcement,
     Stage,
     Forum,
+    Thread,
     Category,
 }
 
@@ -37,6 +46,7 @@ impl Channel {
             kind,
             position,
             parent_id: None,
+            tags: Vec::new(),
         }
     }
 
@@ -45,6 +55,15 @@ impl Channel {
         self
     }
 
+    pub fn with_tags(mut self, tags: Vec<ForumTag>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn tag_name(&self, tag_id: &str) -> Option<String> {
+        self.tags.iter().find(|t| t.id == tag_id).map(|t| t.label())
+    }
+
     pub fn prefix(&self) -> &str {
         match self.kind {
             ChannelType::Text => "# ",
@@ -52,6 +71,7 @@ impl Channel {
             ChannelType::Announcement => "📢 ",
             ChannelType::Stage => "🎙️ ",
             ChannelType::Forum => "💬 ",
+            ChannelType::Thread => "↳ ",
             ChannelType::Category => "",
         }
     }
@@ -59,7 +79,7 @@ impl Channel {
     pub fn is_text_based(&self) -> bool {
         matches!(
             self.kind,
-            ChannelType::Text | ChannelType::Announcement | ChannelType::Forum
+            ChannelType::Text | ChannelType::Announcement | ChannelType::Forum | ChannelType::Thread
         )
     }
 }
@@ -75,10 +95,13 @@ impl ChannelCategory {
 }
 
 /// Organized channel structure with categories
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ChannelList {
     pub categories: Vec<ChannelCategory>,
     pub channels: Vec<Channel>,
+    /// Active threads, with `parent_id` pointing at their parent channel
+    pub threads: Vec<Channel>,
 }
 
 impl ChannelList {
@@ -86,6 +109,7 @@ impl ChannelList {
         Self {
             categories: Vec::new(),
             channels: Vec::new(),
+            threads: Vec::new(),
         }
     }
 
@@ -93,6 +117,36 @@ impl ChannelList {
     pub fn sort(&mut self) {
         self.categories.sort_by_key(|c| c.position);
         self.channels.sort_by_key(|c| c.position);
+        self.threads.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    }
+
+    /// Find a channel or thread by id
+    pub fn find(&self, channel_id: &str) -> Option<&Channel> {
+        self.channels
+            .iter()
+            .chain(self.threads.iter())
+            .find(|c| c.id == channel_id)
+    }
+
+    /// Get active threads under a parent channel
+    pub fn threads_in(&self, channel_id: &str) -> Vec<&Channel> {
+        self.threads
+            .iter()
+            .filter(|t| t.parent_id.as_deref() == Some(channel_id))
+            .collect()
+    }
+
+    pub fn upsert_thread(&mut self, thread: Channel) {
+        if let Some(existing) = self.threads.iter_mut().find(|t| t.id == thread.id) {
+            *existing = thread;
+        } else {
+            self.threads.push(thread);
+        }
+        self.sort();
+    }
+
+    pub fn remove_thread(&mut self, thread_id: &str) {
+        self.threads.retain(|t| t.id != thread_id);
     }
 
     /// Get channels without a category (top-level)
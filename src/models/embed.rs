@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// A rich embed attached to a message, from a bot or a link preview
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Embed {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub provider: Option<String>,
+    pub fields: Vec<EmbedField>,
+    pub footer: Option<String>,
+    pub color: Option<u32>,
+    pub thumbnail: Option<EmbedImage>,
+    pub image: Option<EmbedImage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbedImage {
+    pub url: String,
+}
+
+impl Embed {
+    /// Image cache key for the embed's main image
+    pub fn image_key(message_id: &str, index: usize) -> String {
+        format!("embed_{}_{}_image", message_id, index)
+    }
+
+    /// Image cache key for the embed's thumbnail
+    pub fn thumbnail_key(message_id: &str, index: usize) -> String {
+        format!("embed_{}_{}_thumbnail", message_id, index)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.author.is_none()
+            && self.fields.is_empty()
+            && self.footer.is_none()
+            && self.thumbnail.is_none()
+            && self.image.is_none()
+    }
+}
+
+impl EmbedField {
+    pub fn new(name: impl Into<String>, value: impl Into<String>, inline: bool) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            inline,
+        }
+    }
+}
+
+impl EmbedImage {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
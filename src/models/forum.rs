@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForumTag {
+    pub id: String,
+    pub name: String,
+    pub emoji: Option<String>,
+}
+
+/// A forum post (a thread whose parent is a forum channel)
+#[derive(Debug, Clone)]
+pub struct ForumPost {
+    pub id: String,
+    pub forum_id: String,
+    pub title: String,
+    pub tag_ids: Vec<String>,
+    pub message_count: u32,
+    pub last_message_id: Option<String>,
+}
+
+impl ForumTag {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, emoji: Option<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            emoji,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match &self.emoji {
+            Some(emoji) => format!("{} {}", emoji, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+impl ForumPost {
+    pub fn new(
+        id: impl Into<String>,
+        forum_id: impl Into<String>,
+        title: impl Into<String>,
+        message_count: u32,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            forum_id: forum_id.into(),
+            title: title.into(),
+            tag_ids: Vec::new(),
+            message_count,
+            last_message_id: None,
+        }
+    }
+
+    pub fn with_tags(mut self, tag_ids: Vec<String>) -> Self {
+        self.tag_ids = tag_ids;
+        self
+    }
+
+    pub fn with_last_message(mut self, last_message_id: Option<String>) -> Self {
+        self.last_message_id = last_message_id;
+        self
+    }
+
+    /// Unix time (ms) of the last activity, falling back to the post creation time
+    pub fn last_activity_ms(&self) -> Option<i64> {
+        self.last_message_id
+            .as_deref()
+            .and_then(super::snowflake::timestamp_ms)
+            .or_else(|| super::snowflake::timestamp_ms(&self.id))
+    }
+}
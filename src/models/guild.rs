@@ -1,7 +1,11 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Guild {
     pub id: String,
     pub name: String,
+    pub icon: Option<String>,
     pub expanded: bool,
 }
 
@@ -10,10 +14,26 @@ impl Guild {
         Self {
             id: id.into(),
             name: name.into(),
+            icon: None,
             expanded: false,
         }
     }
 
+    pub fn with_icon(mut self, icon: Option<String>) -> Self {
+        self.icon = icon;
+        self
+    }
+
+    /// Up to two letters used when the icon can't be drawn, e.g. "RP" for "Rust Programming"
+    pub fn initials(&self) -> String {
+        self.name
+            .split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .take(2)
+            .flat_map(char::to_uppercase)
+            .collect()
+    }
+
     pub fn toggle_expanded(&mut self) {
         self.expanded = !self.expanded;
     }
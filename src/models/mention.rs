@@ -0,0 +1,90 @@
+use super::reaction::ReactionEmoji;
+
+/// An inline Discord reference such as `<@id>`, `<:name:id>` or `<t:1700000000:R>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mention {
+    User(String),
+    Channel(String),
+    Role(String),
+    /// Unix seconds and the Discord format letter (`t`, `T`, `d`, `D`, `f`, `F`, `R`)
+    Timestamp(i64, char),
+    /// A custom emoji, always with an `id`
+    Emoji(ReactionEmoji),
+}
+
+/// A piece of message text, either plain or a mention
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Mention(Mention),
+}
+
+impl Mention {
+    /// Parse the inside of a `<...>` token
+    fn parse(inner: &str) -> Option<Self> {
+        let is_id = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+        if let Some(id) = inner.strip_prefix("@&") {
+            is_id(id).then(|| Mention::Role(id.to_string()))
+        } else if let Some(id) = inner.strip_prefix("@!").or_else(|| inner.strip_prefix('@')) {
+            is_id(id).then(|| Mention::User(id.to_string()))
+        } else if let Some(id) = inner.strip_prefix('#') {
+            is_id(id).then(|| Mention::Channel(id.to_string()))
+        } else if inner.starts_with(':') || inner.starts_with("a:") {
+            ReactionEmoji::parse(&format!("<{}>", inner)).map(Mention::Emoji)
+        } else if let Some(rest) = inner.strip_prefix("t:") {
+            let (secs, style) = match rest.split_once(':') {
+                Some((secs, style)) => (secs, style),
+                None => (rest, "f"),
+            };
+            let style = match style {
+                "t" | "T" | "d" | "D" | "f" | "F" | "R" => style.chars().next()?,
+                _ => return None,
+            };
+            Some(Mention::Timestamp(secs.parse().ok()?, style))
+        } else {
+            None
+        }
+    }
+}
+
+/// Split text into plain runs and mentions, leaving malformed tokens as text
+pub fn split_mentions(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut plain_start = 0;
+    let mut pos = 0;
+
+    while let Some(offset) = text[pos..].find('<') {
+        let open = pos + offset;
+        let Some(len) = text[open..].find('>') else {
+            break;
+        };
+        let close = open + len;
+
+        match Mention::parse(&text[open + 1..close]) {
+            Some(mention) => {
+                if plain_start < open {
+                    segments.push(Segment::Text(&text[plain_start..open]));
+                }
+                segments.push(Segment::Mention(mention));
+                pos = close + 1;
+                plain_start = pos;
+            }
+            None => pos = open + 1,
+        }
+    }
+
+    if plain_start < text.len() {
+        segments.push(Segment::Text(&text[plain_start..]));
+    }
+
+    segments
+}
+
+/// All mentions in a piece of text
+pub fn find_mentions(text: &str) -> impl Iterator<Item = Mention> + '_ {
+    split_mentions(text).into_iter().filter_map(|segment| match segment {
+        Segment::Mention(mention) => Some(mention),
+        Segment::Text(_) => None,
+    })
+}
@@ -1,16 +1,66 @@
-#[derive(Debug, Clone)]
+use super::reaction::{Reaction, ReactionEmoji};
+use super::embed::Embed;
+use super::sticker::Sticker;
+use super::outgoing::SendState;
+use serde::{Deserialize, Serialize};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Message {
     pub id: String,
     pub channel_id: String,
+    pub guild_id: Option<String>,
     pub author: String,
     pub author_id: String,
     pub author_avatar: Option<String>,
     pub content: String,
     pub timestamp: String,
     pub attachments: Vec<MessageAttachment>,
+    pub stickers: Vec<Sticker>,
+    pub embeds: Vec<Embed>,
+    pub edited: bool,
+    pub pinned: bool,
+    pub reply_to: Option<ReferencedMessage>,
+    pub reactions: Vec<Reaction>,
+    pub mentioned_users: Vec<MentionedUser>,
+    /// IDs of the roles the message mentions
+    pub mention_roles: Vec<String>,
+    pub mentions_everyone: bool,
+    pub nonce: Option<String>,
+    /// Set while our own message is still in the outbox
+    #[serde(skip)]
+    pub send_state: Option<SendState>,
 }
 
+/// A user mentioned in a message, as resolved by Discord
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MentionedUser {
+    pub id: String,
+    pub name: String,
+}
+
+/// The message a reply points at, as shown in the quoted reply header
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReferencedMessage {
+    pub id: String,
+    pub author: String,
+    pub author_id: String,
+    pub content: String,
+}
+
+/// The message being replied to while composing
 #[derive(Debug, Clone)]
+pub struct ReplyTarget {
+    pub message_id: String,
+    pub author: String,
+    pub mention: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MessageAttachment {
     pub id: String,
     pub filename: String,
@@ -34,12 +84,24 @@ impl Message {
         Self {
             id: id.into(),
             channel_id: channel_id.into(),
+            guild_id: None,
             author: author.into(),
             author_id: author_id.into(),
             author_avatar,
             content: content.into(),
             timestamp: timestamp.into(),
             attachments: Vec::new(),
+            stickers: Vec::new(),
+            embeds: Vec::new(),
+            edited: false,
+            pinned: false,
+            reply_to: None,
+            reactions: Vec::new(),
+            mentioned_users: Vec::new(),
+            mention_roles: Vec::new(),
+            mentions_everyone: false,
+            nonce: None,
+            send_state: None,
         }
     }
 
@@ -47,6 +109,166 @@ impl Message {
         self.attachments = attachments;
         self
     }
+
+    pub fn with_stickers(mut self, stickers: Vec<Sticker>) -> Self {
+        self.stickers = stickers;
+        self
+    }
+
+    pub fn with_embeds(mut self, embeds: Vec<Embed>) -> Self {
+        self.embeds = embeds;
+        self
+    }
+
+    pub fn with_mentioned_users(mut self, mentioned_users: Vec<MentionedUser>) -> Self {
+        self.mentioned_users = mentioned_users;
+        self
+    }
+
+    pub fn with_mention_roles(mut self, mention_roles: Vec<String>) -> Self {
+        self.mention_roles = mention_roles;
+        self
+    }
+
+    pub fn with_mentions_everyone(mut self, mentions_everyone: bool) -> Self {
+        self.mentions_everyone = mentions_everyone;
+        self
+    }
+
+    pub fn with_guild_id(mut self, guild_id: Option<String>) -> Self {
+        self.guild_id = guild_id;
+        self
+    }
+
+    pub fn with_nonce(mut self, nonce: Option<String>) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    pub fn with_send_state(mut self, send_state: Option<SendState>) -> Self {
+        self.send_state = send_state;
+        self
+    }
+
+    pub fn with_edited(mut self, edited: bool) -> Self {
+        self.edited = edited;
+        self
+    }
+
+    pub fn with_pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    pub fn with_reply_to(mut self, reply_to: Option<ReferencedMessage>) -> Self {
+        self.reply_to = reply_to;
+        self
+    }
+
+    pub fn with_reactions(mut self, reactions: Vec<Reaction>) -> Self {
+        self.reactions = reactions;
+        self
+    }
+
+    /// Whether Discord has the message, as opposed to an outbox placeholder with a made-up ID
+    pub fn is_sent(&self) -> bool {
+        self.send_state.is_none()
+    }
+
+    pub fn has_reacted(&self, emoji: &ReactionEmoji) -> bool {
+        self.reactions.iter().any(|r| r.me && r.emoji.same_as(emoji))
+    }
+
+    pub fn add_reaction(&mut self, emoji: ReactionEmoji, by_me: bool) {
+        if let Some(reaction) = self.reactions.iter_mut().find(|r| r.emoji.same_as(&emoji)) {
+            if by_me && reaction.me {
+                return;
+            }
+            reaction.count += 1;
+            reaction.me |= by_me;
+        } else {
+            self.reactions.push(Reaction::new(emoji, 1, by_me));
+        }
+    }
+
+    pub fn remove_reaction(&mut self, emoji: &ReactionEmoji, by_me: bool) {
+        if let Some(reaction) = self.reactions.iter_mut().find(|r| r.emoji.same_as(emoji)) {
+            if by_me && !reaction.me {
+                return;
+            }
+            reaction.count = reaction.count.saturating_sub(1);
+            if by_me {
+                reaction.me = false;
+            }
+        }
+        self.reactions.retain(|r| r.count > 0);
+    }
+}
+
+impl MentionedUser {
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+        }
+    }
+}
+
+impl ReferencedMessage {
+    pub fn new(
+        id: impl Into<String>,
+        author: impl Into<String>,
+        author_id: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            author: author.into(),
+            author_id: author_id.into(),
+            content: content.into(),
+        }
+    }
+
+    /// A reference whose original message was deleted or could not be loaded
+    pub fn unavailable(id: impl Into<String>) -> Self {
+        Self::new(id, "", "", "")
+    }
+
+    pub fn is_available(&self) -> bool {
+        !self.author_id.is_empty()
+    }
+
+    /// First line of the referenced content, cut to fit `max_width` columns
+    pub fn snippet(&self, max_width: usize) -> String {
+        let first_line = self.content.lines().next().unwrap_or("");
+        if first_line.width() <= max_width {
+            return first_line.to_string();
+        }
+        if max_width == 0 {
+            return String::new();
+        }
+
+        // Leave a column for the ellipsis
+        let mut width = 0;
+        let cut: String = first_line
+            .chars()
+            .take_while(|c| {
+                width += c.width().unwrap_or(0);
+                width < max_width
+            })
+            .collect();
+        format!("{}…", cut)
+    }
+}
+
+impl ReplyTarget {
+    pub fn new(message_id: impl Into<String>, author: impl Into<String>) -> Self {
+        Self {
+            message_id: message_id.into(),
+            author: author.into(),
+            mention: true,
+        }
+    }
 }
 
 impl MessageAttachment {
@@ -1,13 +1,27 @@
 mod guild;
 mod channel;
 mod message;
+mod embed;
+mod reaction;
+mod sticker;
 mod file;
 mod dm;
 mod notification;
+mod forum;
+mod outgoing;
+mod search;
+pub mod mention;
+pub mod snowflake;
 
 pub use guild::Guild;
 pub use channel::{Channel, ChannelList, ChannelCategory, ChannelType};
-pub use message::{Message, MessageAttachment};
+pub use message::{Message, MessageAttachment, MentionedUser, ReferencedMessage, ReplyTarget};
+pub use embed::{Embed, EmbedField, EmbedImage};
+pub use reaction::{Reaction, ReactionEmoji};
+pub use sticker::{Sticker, StickerFormat};
 pub use file::AttachedFile;
 pub use dm::{DmChannel, DmUser};
 pub use notification::{Notification, NotificationKind};
+pub use forum::{ForumPost, ForumTag};
+pub use outgoing::{OutgoingMessage, SendState};
+pub use search::{SearchPage, SearchQuery, SearchScope, HAS_FILTERS, SEARCH_PAGE_SIZE};
@@ -0,0 +1,45 @@
+use super::message::ReplyTarget;
+
+/// Where a message we sent is on its way to Discord
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendState {
+    /// Waiting for the gateway to be ready
+    Queued,
+    Sending,
+    Failed(String),
+}
+
+/// A message in the outbox, kept until Discord confirms it so it can be retried
+#[derive(Debug, Clone)]
+pub struct OutgoingMessage {
+    /// Sent with the message and echoed back by Discord, also used as the placeholder's ID
+    pub nonce: String,
+    pub channel_id: String,
+    pub content: String,
+    pub file_paths: Vec<String>,
+    pub reply_to: Option<ReplyTarget>,
+    pub state: SendState,
+}
+
+impl OutgoingMessage {
+    pub fn new(nonce: impl Into<String>, channel_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            nonce: nonce.into(),
+            channel_id: channel_id.into(),
+            content: content.into(),
+            file_paths: Vec::new(),
+            reply_to: None,
+            state: SendState::Queued,
+        }
+    }
+
+    pub fn with_files(mut self, file_paths: Vec<String>) -> Self {
+        self.file_paths = file_paths;
+        self
+    }
+
+    pub fn with_reply_to(mut self, reply_to: Option<ReplyTarget>) -> Self {
+        self.reply_to = reply_to;
+        self
+    }
+}
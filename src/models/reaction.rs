@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Reaction {
+    pub emoji: ReactionEmoji,
+    pub count: u64,
+    pub me: bool,
+}
+
+/// A unicode emoji (no `id`) or a custom guild emoji
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReactionEmoji {
+    pub id: Option<String>,
+    pub name: String,
+    pub animated: bool,
+}
+
+impl Reaction {
+    pub fn new(emoji: ReactionEmoji, count: u64, me: bool) -> Self {
+        Self { emoji, count, me }
+    }
+}
+
+impl ReactionEmoji {
+    pub fn unicode(name: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: name.into(),
+            animated: false,
+        }
+    }
+
+    pub fn custom(id: impl Into<String>, name: impl Into<String>, animated: bool) -> Self {
+        Self {
+            id: Some(id.into()),
+            name: name.into(),
+            animated,
+        }
+    }
+
+    /// Parse user input: `<:name:id>`, `<a:name:id>` or a unicode emoji.
+    /// Plain words are refused rather than sent for Discord to reject.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+
+        if let Some(inner) = input.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+            let mut parts = inner.split(':');
+            let animated = parts.next()? == "a";
+            let name = parts.next()?;
+            let id = parts.next()?;
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            return Some(Self::custom(id, name, animated));
+        }
+
+        looks_like_emoji(input).then(|| Self::unicode(input))
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.id.is_some()
+    }
+
+    /// Text shown for the emoji: the character itself, or `:name:` for custom emoji
+    pub fn label(&self) -> String {
+        if self.is_custom() {
+            format!(":{}:", self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    pub fn same_as(&self, other: &ReactionEmoji) -> bool {
+        match (&self.id, &other.id) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => self.name == other.name,
+            _ => false,
+        }
+    }
+}
+
+/// Whether text is a unicode emoji: pictographs, flags and keycaps, joined by ZWJ and
+/// variation selectors. Letters, spaces and ASCII punctuation never are; digits, `#`
+/// and `*` only as keycaps like 1️⃣.
+fn looks_like_emoji(text: &str) -> bool {
+    let keycap = text.contains('\u{20E3}');
+    text.chars().any(|c| is_pictographic(c) || c == '\u{20E3}')
+        && text.chars().all(|c| {
+            is_pictographic(c)
+                || matches!(c, '\u{200D}' | '\u{FE0E}' | '\u{FE0F}' | '\u{20E3}' | '\u{E0020}'..='\u{E007F}')
+                || (keycap && matches!(c, '0'..='9' | '#' | '*'))
+        })
+}
+
+// Symbol and pictograph blocks, including the regional indicators that make up flags
+fn is_pictographic(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00A9}' | '\u{00AE}' | '\u{203C}' | '\u{2049}' | '\u{2122}' | '\u{2139}'
+            | '\u{2190}'..='\u{21FF}'
+            | '\u{2300}'..='\u{23FF}'
+            | '\u{24C2}'
+            | '\u{25A0}'..='\u{25FF}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{2900}'..='\u{297F}'
+            | '\u{2B00}'..='\u{2BFF}'
+            | '\u{3030}' | '\u{303D}' | '\u{3297}' | '\u{3299}'
+            | '\u{1F000}'..='\u{1FAFF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_unicode_emoji_sequences() {
+        for emoji in ["👍", "❤️", "👩‍💻", "👍🏽", "🇫🇷", "1️⃣", "#️⃣", "🏴\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}"] {
+            assert_eq!(ReactionEmoji::parse(emoji), Some(ReactionEmoji::unicode(emoji)), "{}", emoji);
+        }
+    }
+
+    #[test]
+    fn parses_custom_emoji() {
+        assert_eq!(ReactionEmoji::parse("<:ferris:123>"), Some(ReactionEmoji::custom("123", "ferris", false)));
+        assert_eq!(ReactionEmoji::parse("<a:party:456>"), Some(ReactionEmoji::custom("456", "party", true)));
+        assert_eq!(ReactionEmoji::parse("<:ferris:abc>"), None);
+    }
+
+    #[test]
+    fn refuses_text_that_is_not_an_emoji() {
+        for text in ["", "ok", "1", "é", "日本", "👍 👍", "👍a", "-"] {
+            assert_eq!(ReactionEmoji::parse(text), None, "{}", text);
+        }
+    }
+}
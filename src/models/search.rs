@@ -0,0 +1,102 @@
+use super::message::Message;
+
+/// Discord always returns this many hits per search page
+pub const SEARCH_PAGE_SIZE: usize = 25;
+
+/// Values accepted by the `has:` filter
+pub const HAS_FILTERS: &[&str] = &["link", "embed", "file", "image", "video", "sound", "sticker", "poll"];
+
+/// What a search runs against: a whole guild, or a single DM
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchScope {
+    Guild(String),
+    Channel(String),
+}
+
+/// A search with its filters resolved to IDs, as sent to Discord
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub content: String,
+    pub author_ids: Vec<String>,
+    pub channel_ids: Vec<String>,
+    pub has: Vec<String>,
+    /// Only messages newer than this snowflake
+    pub min_id: Option<String>,
+    /// Only messages older than this snowflake
+    pub max_id: Option<String>,
+}
+
+/// One page of search hits, newest first
+#[derive(Debug, Clone)]
+pub struct SearchPage {
+    pub total_results: usize,
+    pub messages: Vec<Message>,
+}
+
+impl SearchQuery {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Query string parameters, with repeated keys for multi-valued filters
+    pub fn params(&self, offset: usize) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if !self.content.is_empty() {
+            params.push(("content", self.content.clone()));
+        }
+        params.extend(self.author_ids.iter().map(|id| ("author_id", id.clone())));
+        params.extend(self.channel_ids.iter().map(|id| ("channel_id", id.clone())));
+        params.extend(self.has.iter().map(|has| ("has", has.clone())));
+        if let Some(min_id) = &self.min_id {
+            params.push(("min_id", min_id.clone()));
+        }
+        if let Some(max_id) = &self.max_id {
+            params.push(("max_id", max_id.clone()));
+        }
+        if offset > 0 {
+            params.push(("offset", offset.to_string()));
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_repeat_keys_for_each_value() {
+        let query = SearchQuery {
+            content: "deploy".to_string(),
+            author_ids: vec!["1".to_string(), "2".to_string()],
+            channel_ids: vec!["3".to_string()],
+            has: vec!["link".to_string(), "file".to_string()],
+            min_id: Some("10".to_string()),
+            max_id: Some("20".to_string()),
+        };
+        let params = query.params(50);
+        let params: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        assert_eq!(
+            params,
+            [
+                ("content", "deploy"),
+                ("author_id", "1"),
+                ("author_id", "2"),
+                ("channel_id", "3"),
+                ("has", "link"),
+                ("has", "file"),
+                ("min_id", "10"),
+                ("max_id", "20"),
+                ("offset", "50"),
+            ]
+        );
+    }
+
+    #[test]
+    fn params_skip_unset_filters_and_the_first_offset() {
+        let query = SearchQuery { has: vec!["image".to_string()], ..SearchQuery::default() };
+        assert_eq!(query.params(0), [("has", "image".to_string())]);
+        assert!(!query.is_empty());
+        assert!(SearchQuery::default().params(0).is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+/// Milliseconds between the Unix epoch and the Discord epoch (2015-01-01)
+const DISCORD_EPOCH_MS: i64 = 1_420_070_400_000;
+
+/// Unix time in milliseconds encoded in a Discord snowflake id
+pub fn timestamp_ms(id: &str) -> Option<i64> {
+    let id = id.parse::<u64>().ok()?;
+    Some((id >> 22) as i64 + DISCORD_EPOCH_MS)
+}
+
+/// A snowflake as a number, for ordering. IDs that do not parse sort first.
+pub fn value(id: &str) -> u64 {
+    id.parse().unwrap_or(0)
+}
+
+/// The lowest snowflake Discord could have made at a Unix time in milliseconds
+pub fn from_timestamp_ms(timestamp_ms: i64) -> String {
+    (((timestamp_ms - DISCORD_EPOCH_MS).max(0) as u64) << 22).to_string()
+}
+
+/// A fresh snowflake for the current time, for use as a message nonce
+pub fn generate() -> String {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    // Low bits keep IDs made in the same millisecond distinct
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(DISCORD_EPOCH_MS);
+    let increment = SEQUENCE.fetch_add(1, Ordering::Relaxed) & 0xFFF;
+
+    ((((now_ms - DISCORD_EPOCH_MS).max(0) as u64) << 22) | increment).to_string()
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StickerFormat {
+    #[default]
+    Png,
+    Apng,
+    Gif,
+    Lottie,
+}
+
+/// A sticker sent with a message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sticker {
+    pub id: String,
+    pub name: String,
+    pub format: StickerFormat,
+}
+
+impl Sticker {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, format: StickerFormat) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            format,
+        }
+    }
+
+    /// CDN file extension for stickers we can draw as images. Lottie stickers are
+    /// vector animations and have none.
+    pub fn image_extension(&self) -> Option<&'static str> {
+        match self.format {
+            StickerFormat::Png | StickerFormat::Apng => Some("png"),
+            StickerFormat::Gif => Some("gif"),
+            StickerFormat::Lottie => None,
+        }
+    }
+
+    /// Key used for the sticker in the image caches
+    pub fn cache_key(&self) -> String {
+        format!("sticker_{}", self.id)
+    }
+}
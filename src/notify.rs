@@ -0,0 +1,82 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use zbus::{dbus_proxy, zvariant::Value, Connection};
+
+// Notification bodies are cut down to this many characters
+const MAX_BODY_CHARS: usize = 200;
+
+// Milliseconds a notification stays up, or -1 for the server default
+const EXPIRE_TIMEOUT: i32 = -1;
+
+#[dbus_proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+}
+
+/// Sends desktop notifications through `org.freedesktop.Notifications` on the session bus
+#[derive(Clone)]
+pub struct DesktopNotifier {
+    proxy: NotificationsProxy<'static>,
+}
+
+impl DesktopNotifier {
+    pub async fn connect() -> Result<Self> {
+        let connection = Connection::session()
+            .await
+            .context("Failed to connect to the session bus")?;
+        let proxy = NotificationsProxy::new(&connection)
+            .await
+            .context("Failed to create notifications proxy")?;
+        Ok(Self { proxy })
+    }
+
+    /// Show a notification and return the id the server gave it
+    pub async fn notify(&self, summary: &str, body: &str) -> Result<u32> {
+        let body = escape_markup(&truncate(body, MAX_BODY_CHARS));
+        let mut hints = HashMap::new();
+        hints.insert("category", Value::from("im.received"));
+
+        let id = self.proxy
+            .notify("remycord", 0, "", summary, &body, &[], hints, EXPIRE_TIMEOUT)
+            .await
+            .context("Notification server rejected the notification")?;
+        Ok(id)
+    }
+
+    /// Show a notification without waiting on the bus
+    pub fn send(&self, summary: String, body: String) {
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            let _ = notifier.notify(&summary, &body).await;
+        });
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+// Servers may treat the body as a small subset of HTML
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,63 @@
+use crate::app::App;
+use crate::ui::utils::centered_rect;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let kb = &app.config.keybinds;
+    let names = app.account_names();
+
+    let popup = centered_rect(50, 50, area);
+    f.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Accounts")
+        .title_bottom(format!(
+            " {}: switch, {}: set token, {}: remove token, {}: close ",
+            kb.select.key, kb.set_token.key, kb.remove_token.key, kb.back.key
+        ))
+        .border_style(Style::default().fg(theme.get_color("base0A")));
+
+    if names.is_empty() {
+        let empty = Paragraph::new(Span::styled(
+            "No accounts configured, add them under [accounts] in config.toml",
+            Style::default().fg(theme.get_color("base03")).add_modifier(Modifier::ITALIC),
+        ))
+        .block(block);
+        f.render_widget(empty, popup);
+        return;
+    }
+
+    let items: Vec<ListItem> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let is_selected = i == app.accounts_selected_idx;
+            let is_current = app.account.as_ref() == Some(name);
+
+            let marker = if is_selected { "› " } else { "  " };
+            let mut name_style = Style::default().fg(theme.get_color("base05"));
+            if is_selected {
+                name_style = name_style.fg(theme.get_color("base0A")).add_modifier(Modifier::BOLD);
+            }
+
+            let mut spans = vec![
+                Span::styled(marker, Style::default().fg(theme.get_color("base0A"))),
+                Span::styled(name.clone(), name_style),
+            ];
+            if is_current {
+                spans.push(Span::styled(" (signed in)", Style::default().fg(theme.get_color("base0B"))));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    f.render_widget(List::new(items).block(block), popup);
+}
@@ -0,0 +1,170 @@
+use crate::app::App;
+use crate::models::Embed;
+use crate::ui::emoji::{self, InlineEmoji};
+use crate::ui::image::ImageRenderer;
+use crate::ui::{markdown, mentions};
+use crate::ui::utils::wrap_spans;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use ratatui_image::StatefulImage;
+
+// Embeds stay readable on wide terminals instead of stretching across the pane
+pub const EMBED_MAX_WIDTH: u16 = 72;
+
+/// An embed laid out for a given width, ready to measure and draw
+pub struct EmbedLayout {
+    edge_color: Color,
+    border_color: Color,
+    lines: Vec<Line<'static>>,
+    emojis: Vec<InlineEmoji>,
+    /// Image cache key, width and height of each image in draw order
+    images: Vec<(String, u16, u16)>,
+    width: u16,
+}
+
+impl EmbedLayout {
+    pub fn height(&self) -> u16 {
+        let image_height: u16 = self.images.iter().map(|(_, _, h)| *h).sum();
+        self.lines.len() as u16 + image_height + 2
+    }
+}
+
+pub fn layout(app: &App, message_id: &str, index: usize, embed: &Embed, width: u16) -> EmbedLayout {
+    let theme = app.theme();
+    let width = width.min(EMBED_MAX_WIDTH);
+    // Borders on both sides plus a column of padding
+    let text_width = width.saturating_sub(3).max(1);
+    let dim = Style::default().fg(theme.get_color("base04"));
+    let text = Style::default().fg(theme.get_color("base05"));
+    let bold = text.add_modifier(Modifier::BOLD);
+
+    let mut lines = Vec::new();
+    let mut emoji_slots = mentions::emoji_slots(
+        app,
+        [&embed.provider, &embed.author, &embed.title, &embed.description, &embed.footer]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .chain(embed.fields.iter().flat_map(|field| [field.name.as_str(), field.value.as_str()])),
+    );
+    let plain = |lines: &mut Vec<Line<'static>>, value: &str, style: Style| {
+        lines.extend(wrap_spans(vec![Span::styled(value.to_string(), style)], text_width as usize, Vec::new(), Vec::new()));
+    };
+
+    if let Some(provider) = &embed.provider {
+        plain(&mut lines, provider, dim);
+    }
+    if let Some(author) = &embed.author {
+        plain(&mut lines, author, bold);
+    }
+    if let Some(title) = &embed.title {
+        let mut style = Style::default().fg(theme.get_color("base0D")).add_modifier(Modifier::BOLD);
+        if embed.url.is_some() {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        plain(&mut lines, title, style);
+    }
+    if let Some(description) = &embed.description {
+        lines.extend(markdown::render(app, description, text_width, text, false, &mut emoji_slots));
+    }
+    for field in &embed.fields {
+        if field.inline {
+            let mut field_lines = markdown::render(app, &field.value, text_width, text, false, &mut emoji_slots);
+            if field_lines.len() == 1 && field.name.len() + field_lines[0].width() + 2 <= text_width as usize {
+                let mut spans = vec![Span::styled(format!("{}: ", field.name), bold)];
+                spans.append(&mut field_lines[0].spans);
+                lines.push(Line::from(spans));
+                continue;
+            }
+        }
+        plain(&mut lines, &field.name, bold);
+        lines.extend(markdown::render(app, &field.value, text_width, text, false, &mut emoji_slots));
+    }
+    if let Some(footer) = &embed.footer {
+        plain(&mut lines, footer, dim);
+    }
+
+    let emojis = emoji::take_placeholders(&mut lines, &emoji_slots);
+
+    let mut images = Vec::new();
+    let image_keys = [
+        embed.thumbnail.as_ref().map(|_| Embed::thumbnail_key(message_id, index)),
+        embed.image.as_ref().map(|_| Embed::image_key(message_id, index)),
+    ];
+    for key in image_keys.into_iter().flatten() {
+        let size = app.image_renderer.get_attachment_width(&key)
+            .zip(app.image_renderer.get_attachment_height(&key));
+        if let Some((img_width, img_height)) = size {
+            images.push((key, img_width.min(text_width), img_height));
+        }
+    }
+
+    let edge_color = match embed.color {
+        Some(color) if color != 0 => Color::Rgb((color >> 16) as u8, (color >> 8) as u8, color as u8),
+        _ => theme.get_color("base03"),
+    };
+
+    EmbedLayout {
+        edge_color,
+        border_color: theme.get_color("base02"),
+        lines,
+        emojis,
+        images,
+        width,
+    }
+}
+
+/// Draw an embed with its top-left corner at `x`, `y`, clipped to `bottom`
+pub fn draw(f: &mut Frame, renderer: &mut ImageRenderer, layout: &EmbedLayout, x: u16, y: u16, bottom: u16) {
+    let area = Rect {
+        x,
+        y,
+        width: layout.width,
+        height: layout.height().min(bottom.saturating_sub(y)),
+    };
+    if area.height == 0 {
+        return;
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(layout.border_color));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // The embed colour runs down the left edge
+    let edge: Vec<Line> = (0..area.height)
+        .map(|_| Line::from(Span::styled("┃", Style::default().fg(layout.edge_color))))
+        .collect();
+    f.render_widget(Paragraph::new(edge), Rect { width: 1, ..area });
+
+    let text_area = Rect {
+        x: inner.x + 1,
+        width: inner.width.saturating_sub(1),
+        ..inner
+    };
+    f.render_widget(Paragraph::new(layout.lines.clone()), text_area);
+    emoji::draw(f, renderer, text_area, 0, &layout.emojis);
+
+    let mut image_y = text_area.y + layout.lines.len() as u16;
+    for (key, width, height) in &layout.images {
+        if image_y >= inner.bottom() {
+            break;
+        }
+        if let Some(mut protocol) = renderer.get_attachment(key) {
+            let img_area = Rect {
+                x: text_area.x,
+                y: image_y,
+                width: (*width).min(text_area.width),
+                height: (*height).min(inner.bottom() - image_y),
+            };
+            f.render_stateful_widget(StatefulImage::default(), img_area, &mut protocol);
+        }
+        image_y += height;
+    }
+}
@@ -0,0 +1,127 @@
+use crate::ui::image::ImageRenderer;
+use ratatui::{
+    layout::Rect,
+    text::{Line, Span},
+    Frame,
+};
+use ratatui_image::StatefulImage;
+use std::collections::HashSet;
+use unicode_width::UnicodeWidthChar;
+
+// Placeholders are drawn from the Basic Multilingual Plane's private use area
+const PLACEHOLDER_BASE: u32 = 0xE000;
+const PLACEHOLDER_LIMIT: u32 = 0xF8FF;
+
+/// Where an inline emoji image goes, relative to the first rendered content line
+#[derive(Debug, Clone)]
+pub struct InlineEmoji {
+    pub id: String,
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+}
+
+/// Custom emoji drawn as images in a piece of text. Each one is laid out as two
+/// private-use characters the text does not use itself, then swapped for spaces
+/// and drawn over with the image.
+#[derive(Debug, Default)]
+pub struct EmojiSlots {
+    /// Private-use characters that appear in the text, such as Nerd Font icons
+    reserved: HashSet<char>,
+    /// Placeholder character and emoji ID, in order of appearance
+    slots: Vec<(char, String)>,
+}
+
+impl EmojiSlots {
+    /// Slots for emoji in `texts`, which should be everything drawn in the same lines
+    pub fn new<'a>(texts: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut slots = Self::default();
+        for text in texts {
+            slots.reserve(text);
+        }
+        slots
+    }
+
+    /// Keep placeholders clear of the private-use characters in `text`. Only takes effect
+    /// for placeholders handed out afterwards.
+    pub fn reserve(&mut self, text: &str) {
+        self.reserved.extend(text.chars().filter(|c| is_private_use(*c)));
+    }
+
+    /// Placeholder text for the next emoji, or `None` once the private use area runs out
+    pub fn push(&mut self, id: String) -> Option<String> {
+        let start = self.slots.last().map_or(PLACEHOLDER_BASE, |(c, _)| *c as u32 + 1);
+        let c = (start..=PLACEHOLDER_LIMIT)
+            .filter_map(char::from_u32)
+            .find(|c| !self.reserved.contains(c))?;
+        self.slots.push((c, id));
+        Some([c, c].iter().collect())
+    }
+
+    fn id_of(&self, c: char) -> Option<&str> {
+        self.slots.iter().find(|(slot, _)| *slot == c).map(|(_, id)| id.as_str())
+    }
+}
+
+/// Blank out placeholders in wrapped lines and return where each emoji landed.
+/// Private-use characters that are not placeholders are left alone.
+pub fn take_placeholders(lines: &mut [Line<'static>], slots: &EmojiSlots) -> Vec<InlineEmoji> {
+    let mut found: Vec<InlineEmoji> = Vec::new();
+    if slots.slots.is_empty() {
+        return found;
+    }
+
+    for (y, line) in lines.iter_mut().enumerate() {
+        let mut x = 0u16;
+        for span in line.spans.iter_mut() {
+            if !span.content.chars().any(|c| slots.id_of(c).is_some()) {
+                x += span.width() as u16;
+                continue;
+            }
+
+            let mut text = String::with_capacity(span.content.len());
+            for c in span.content.chars() {
+                if let Some(id) = slots.id_of(c) {
+                    match found.last_mut() {
+                        // Second half of the same emoji on the same row
+                        Some(last) if last.id == id && last.y == y as u16 && last.x + last.width == x => {
+                            last.width += 1;
+                        }
+                        _ => found.push(InlineEmoji { id: id.to_string(), x, y: y as u16, width: 1 }),
+                    }
+                    text.push(' ');
+                } else {
+                    text.push(c);
+                }
+                x += c.width().unwrap_or(0) as u16;
+            }
+            *span = Span::styled(text, span.style);
+        }
+    }
+
+    found
+}
+
+/// Draw emoji images over their blanked placeholders. `top` is the row of
+/// `area` holding the first content line.
+pub fn draw(f: &mut Frame, renderer: &mut ImageRenderer, area: Rect, top: u16, emojis: &[InlineEmoji]) {
+    for inline in emojis {
+        let y = top + inline.y;
+        if y >= area.height || inline.x >= area.width {
+            continue;
+        }
+        if let Some(protocol) = renderer.get_emoji(&inline.id) {
+            let emoji_area = Rect {
+                x: area.x + inline.x,
+                y: area.y + y,
+                width: inline.width.min(area.width - inline.x),
+                height: 1,
+            };
+            f.render_stateful_widget(StatefulImage::default(), emoji_area, protocol);
+        }
+    }
+}
+
+fn is_private_use(c: char) -> bool {
+    (PLACEHOLDER_BASE..=PLACEHOLDER_LIMIT).contains(&(c as u32))
+}
@@ -0,0 +1,203 @@
+use crate::app::{App, AppMode, DraftField};
+use crate::ui::utils::{centered_rect, format_relative_time};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+// Each post takes a title line and a details line
+const POST_HEIGHT: usize = 2;
+
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    draw_header(f, app, chunks[0]);
+    draw_posts(f, app, chunks[1]);
+    draw_footer(f, app, chunks[2]);
+
+    if app.mode == AppMode::ForumCompose {
+        draw_compose(f, app, area);
+    }
+}
+
+fn draw_header(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let forum_name = app.current_forum().map(|c| c.name.clone()).unwrap_or_else(|| "Unknown".to_string());
+    let guild_name = app
+        .selected_forum
+        .as_deref()
+        .and_then(|id| app.guild_id_for_channel(id))
+        .and_then(|guild_id| app.guilds.iter().find(|g| g.id == guild_id))
+        .map(|g| g.name.clone())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let header = Paragraph::new(format!("{} > 💬 {}", guild_name, forum_name))
+        .block(Block::default().borders(Borders::ALL).title("Forum").border_style(Style::default().fg(theme.get_color("base03"))))
+        .style(Style::default().fg(theme.get_color("base0B")));
+    f.render_widget(header, area);
+}
+
+fn draw_posts(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let border_style = Style::default().fg(theme.get_color("base0A"));
+    let block = Block::default().borders(Borders::ALL).title("Posts").border_style(border_style);
+
+    let posts = app.current_forum_posts();
+    if posts.is_empty() {
+        let text = if app.loading_forum_posts || !app.selected_forum.as_ref().is_some_and(|id| app.forum_posts.contains_key(id)) {
+            "Loading posts..."
+        } else {
+            "No posts yet"
+        };
+        let empty = Paragraph::new(text)
+            .block(block)
+            .style(Style::default().fg(theme.get_color("base04")));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let forum = app.current_forum();
+    let visible = (area.height.saturating_sub(2) as usize / POST_HEIGHT).max(1);
+    let start = app.forum_selected_idx.saturating_sub(visible - 1);
+
+    let items: Vec<ListItem> = posts
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(visible)
+        .map(|(i, post)| {
+            let is_selected = i == app.forum_selected_idx;
+            let title_style = if is_selected {
+                Style::default().fg(theme.get_color("base0A")).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.get_color("base05")).add_modifier(Modifier::BOLD)
+            };
+            let marker = if is_selected { "› " } else { "  " };
+
+            let mut details = vec![Span::raw("  ")];
+            for tag_id in &post.tag_ids {
+                if let Some(name) = forum.and_then(|f| f.tag_name(tag_id)) {
+                    details.push(Span::styled(
+                        format!("[{}] ", name),
+                        Style::default().fg(theme.get_color("base0C")),
+                    ));
+                }
+            }
+
+            let mut summary = format!(
+                "{} repl{}",
+                post.message_count,
+                if post.message_count == 1 { "y" } else { "ies" }
+            );
+            if let Some(ms) = post.last_activity_ms() {
+                summary.push_str(&format!(" · {}", format_relative_time(ms)));
+            }
+            details.push(Span::styled(summary, Style::default().fg(theme.get_color("base03"))));
+
+            ListItem::new(vec![
+                Line::from(Span::styled(format!("{}{}", marker, post.title), title_style)),
+                Line::from(details),
+            ])
+        })
+        .collect();
+
+    f.render_widget(List::new(items).block(block), area);
+}
+
+fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let kb = &app.config.keybinds;
+
+    let hint = format!(
+        "{}: open post  {}: new post  {}: back",
+        kb.select.key, kb.new_post.key, kb.back.key
+    );
+    let footer = Paragraph::new(hint)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(theme.get_color("base03"))))
+        .style(Style::default().fg(theme.get_color("base04")));
+    f.render_widget(footer, area);
+}
+
+fn draw_compose(f: &mut Frame, app: &App, area: Rect) {
+    let Some(draft) = &app.forum_draft else {
+        return;
+    };
+    let theme = app.theme();
+    let tags = app.current_forum().map(|c| c.tags.as_slice()).unwrap_or(&[]);
+
+    let popup = centered_rect(70, 60, area);
+    f.render_widget(Clear, popup);
+
+    let outer = Block::default()
+        .borders(Borders::ALL)
+        .title("New Post (Tab: next field, Enter: post, Esc: cancel)")
+        .border_style(Style::default().fg(theme.get_color("base0A")));
+    let inner = outer.inner(popup);
+    f.render_widget(outer, popup);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(3),
+        ])
+        .split(inner);
+
+    let field_block = |title: &'static str, field: DraftField| {
+        let color = if draft.field == field { "base0A" } else { "base03" };
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(theme.get_color(color)))
+    };
+    let text_style = Style::default().fg(theme.get_color("base05"));
+
+    let title = Paragraph::new(draft.title.as_str())
+        .block(field_block("Title", DraftField::Title))
+        .style(text_style);
+    f.render_widget(title, chunks[0]);
+
+    let tag_line = if tags.is_empty() {
+        Line::from(Span::styled("This forum has no tags", Style::default().fg(theme.get_color("base03"))))
+    } else {
+        let spans: Vec<Span> = tags
+            .iter()
+            .enumerate()
+            .map(|(i, tag)| {
+                let applied = draft.tag_ids.contains(&tag.id);
+                let mut style = if applied {
+                    Style::default().fg(theme.get_color("base0B")).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.get_color("base04"))
+                };
+                if draft.field == DraftField::Tags && i == draft.tag_cursor {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                let check = if applied { "✓" } else { " " };
+                Span::styled(format!("[{}{}] ", check, tag.label()), style)
+            })
+            .collect();
+        Line::from(spans)
+    };
+    f.render_widget(
+        Paragraph::new(tag_line).block(field_block("Tags (Space to toggle)", DraftField::Tags)),
+        chunks[1],
+    );
+
+    let content = Paragraph::new(draft.content.as_str())
+        .block(field_block("Message", DraftField::Content))
+        .wrap(Wrap { trim: false })
+        .style(text_style);
+    f.render_widget(content, chunks[2]);
+}
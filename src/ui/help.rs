@@ -1,4 +1,5 @@
 use crate::app::App;
+use crate::ui::utils::format_modifiers;
 use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
@@ -22,15 +23,42 @@ pub fn draw(f: &mut Frame, app: &App, area: Rect) {
         Line::from(format!("  {}      - Expand server / Select channel", kb.select.key)),
         Line::from(format!("  {}      - Go back / Quit", kb.quit.key)),
         Line::from(format!("  {}      - Settings", kb.settings.key)),
+        Line::from(format!("  {}      - Mute/unmute channel notifications", kb.mute_channel.key)),
+        Line::from(format!("  {}      - Toggle do not disturb", kb.toggle_dnd.key)),
+        Line::from(format!("  {}      - Switch account", kb.switch_account.key)),
+        Line::from(""),
+        Line::from("In Accounts:"),
+        Line::from(format!("  {}      - Sign in to selected account", kb.select.key)),
+        Line::from(format!("  {}      - Set token for selected account", kb.set_token.key)),
+        Line::from(format!("  {}      - Remove token from the keyring", kb.remove_token.key)),
         Line::from(""),
         Line::from("In Messages:"),
         Line::from(format!("  {}      - Enter input mode", kb.input_mode.key)),
         Line::from(format!("  {}      - Attach file", kb.attach_file.key)),
+        Line::from(format!("  {}      - Reply to selected message", kb.reply.key)),
+        Line::from(format!("  {}  - Jump to replied message", kb.select.key)),
+        Line::from(format!("  {}      - Edit selected message", kb.edit_message.key)),
+        Line::from(format!("  {}      - Add/remove a reaction", kb.react.key)),
+        Line::from(format!("  {}      - Delete selected message", kb.delete_message.key)),
+        Line::from(format!("  {}      - Retry a message that failed to send", kb.retry_send.key)),
+        Line::from(format!("  {}      - Show pinned messages", kb.pins.key)),
+        Line::from(format!("  {}      - Pin/unpin selected message", kb.toggle_pin.key)),
+        Line::from(format!("  {}      - Search messages", kb.search.key)),
+        Line::from(format!("  {}      - Back to sidebar", kb.back.key)),
+        Line::from(""),
+        Line::from("In Forums:"),
+        Line::from(format!("  {}      - Open selected post", kb.select.key)),
+        Line::from(format!("  {}      - Create a new post", kb.new_post.key)),
         Line::from(format!("  {}      - Back to sidebar", kb.back.key)),
         Line::from(""),
         Line::from("Input Mode:"),
         Line::from(format!("  {}      - Send message", kb.send_message.key)),
         Line::from(format!("  {}      - Cancel", kb.cancel_input.key)),
+        Line::from(format!(
+            "  {}{} - Toggle reply ping",
+            format_modifiers(&kb.toggle_reply_mention.modifiers),
+            kb.toggle_reply_mention.key
+        )),
         Line::from(format!("  {} / {}  - Move cursor", kb.cursor_left.key, kb.cursor_right.key)),
         Line::from(""),
         Line::from("Select a server to get started!"),
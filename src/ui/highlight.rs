@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxSet};
+
+// Highlighting runs every frame, so parsed blocks are memoised up to this many entries
+const MAX_CACHED_BLOCKS: usize = 256;
+
+/// A run of code text and the base16 slot it should be drawn with
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub text: String,
+    pub slot: Option<&'static str>,
+    pub italic: bool,
+}
+
+// Scope prefixes mapped onto the base16 styling guidelines, most specific first
+const SCOPE_SLOTS: &[(&str, &str)] = &[
+    ("comment", "base03"),
+    ("constant.character.escape", "base0C"),
+    ("string.regexp", "base0C"),
+    ("string", "base0B"),
+    ("constant.numeric", "base09"),
+    ("constant.language", "base09"),
+    ("constant", "base09"),
+    ("keyword.operator", "base05"),
+    ("keyword", "base0E"),
+    ("storage", "base0E"),
+    ("entity.name.function", "base0D"),
+    ("support.function", "base0D"),
+    ("variable.function", "base0D"),
+    ("entity.name.tag", "base08"),
+    ("entity.other.attribute-name", "base09"),
+    ("entity.name", "base0A"),
+    ("support.type", "base0A"),
+    ("support.class", "base0A"),
+    ("support", "base0C"),
+    ("variable.parameter", "base08"),
+    ("variable.language", "base08"),
+    ("variable", "base08"),
+    ("markup.heading", "base0D"),
+    ("markup.inserted", "base0B"),
+    ("markup.deleted", "base08"),
+    ("markup.changed", "base0E"),
+    ("invalid", "base08"),
+];
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn cache() -> &'static Mutex<HashMap<u64, Vec<Vec<Token>>>> {
+    static CACHE: OnceLock<Mutex<HashMap<u64, Vec<Vec<Token>>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Highlight a code block, one token list per line.
+/// Returns `None` when the language has no bundled grammar.
+pub fn highlight(lang: &str, code: &[String]) -> Option<Vec<Vec<Token>>> {
+    if lang.is_empty() {
+        return None;
+    }
+
+    let mut hasher = DefaultHasher::new();
+    (lang, code).hash(&mut hasher);
+    let key = hasher.finish();
+
+    if let Some(lines) = cache().lock().ok()?.get(&key) {
+        return Some(lines.clone());
+    }
+
+    let syntaxes = syntax_set();
+    let syntax = syntaxes.find_syntax_by_token(lang)?;
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut lines = Vec::with_capacity(code.len());
+
+    for line in code {
+        let line = format!("{}\n", line);
+        let ops = state.parse_line(&line, syntaxes).ok()?;
+        let mut tokens = Vec::new();
+        let mut last = 0;
+
+        for (pos, op) in ops {
+            if pos > last {
+                tokens.push(token(&line[last..pos], &stack));
+                last = pos;
+            }
+            stack.apply(&op).ok()?;
+        }
+        if last < line.len() {
+            tokens.push(token(&line[last..], &stack));
+        }
+        if let Some(end) = tokens.last_mut() {
+            end.text.truncate(end.text.trim_end_matches('\n').len());
+        }
+        tokens.retain(|t| !t.text.is_empty());
+
+        lines.push(tokens);
+    }
+
+    if let Ok(mut cache) = cache().lock() {
+        if cache.len() >= MAX_CACHED_BLOCKS {
+            cache.clear();
+        }
+        cache.insert(key, lines.clone());
+    }
+
+    Some(lines)
+}
+
+fn token(text: &str, stack: &ScopeStack) -> Token {
+    let scopes: Vec<String> = stack
+        .as_slice()
+        .iter()
+        .rev()
+        .map(|scope| scope.build_string())
+        .collect();
+
+    let slot = scopes.iter().find_map(|scope| {
+        SCOPE_SLOTS
+            .iter()
+            .find(|(prefix, _)| scope.starts_with(prefix))
+            .map(|(_, slot)| *slot)
+    });
+
+    Token {
+        text: text.replace('\t', "    "),
+        slot,
+        italic: scopes.iter().any(|scope| scope.starts_with("comment")),
+    }
+}
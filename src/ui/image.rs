@@ -6,10 +6,16 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use tokio::fs;
 
+use crate::config::NetworkSettings;
+
 pub struct ImageRenderer {
     picker: Picker,
     avatar_cache: HashMap<String, CachedAvatar>,
     attachment_cache: HashMap<String, CachedAttachment>,
+    emoji_cache: HashMap<String, StatefulProtocol>,
+    icon_cache: HashMap<String, StatefulProtocol>,
+    cdn_base: String,
+    media_base: String,
 }
 
 struct CachedAvatar {
@@ -25,14 +31,41 @@ struct CachedAttachment {
 }
 
 impl ImageRenderer {
-    pub fn new() -> Self {
+    /// Images are fetched from the CDN and media hosts in `network`
+    pub fn new(network: &NetworkSettings) -> Self {
         let picker = Picker::from_query_stdio()
             .unwrap_or_else(|_| Picker::from_fontsize((8, 12)));
+        Self::with_picker(picker, network)
+    }
+
+    /// A renderer that only draws halfblocks, without querying the terminal
+    #[cfg(test)]
+    pub fn halfblocks(network: &NetworkSettings) -> Self {
+        Self::with_picker(Picker::from_fontsize((8, 12)), network)
+    }
 
+    fn with_picker(picker: Picker, network: &NetworkSettings) -> Self {
         Self {
             picker,
             avatar_cache: HashMap::new(),
             attachment_cache: HashMap::new(),
+            emoji_cache: HashMap::new(),
+            icon_cache: HashMap::new(),
+            cdn_base: network.cdn_base.trim_end_matches('/').to_string(),
+            media_base: network.media_base.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// The same renderer with nothing loaded, so the terminal is not queried again
+    pub fn without_caches(&self) -> Self {
+        Self {
+            picker: self.picker.clone(),
+            avatar_cache: HashMap::new(),
+            attachment_cache: HashMap::new(),
+            emoji_cache: HashMap::new(),
+            icon_cache: HashMap::new(),
+            cdn_base: self.cdn_base.clone(),
+            media_base: self.media_base.clone(),
         }
     }
 
@@ -49,9 +82,9 @@ impl ImageRenderer {
         }
     }
 
-    pub fn get_avatar_url(user_id: &str, avatar_hash: Option<&str>) -> String {
+    pub fn get_avatar_url(&self, user_id: &str, avatar_hash: Option<&str>) -> String {
         if let Some(hash) = avatar_hash {
-            format!("https://cdn.discordapp.com/avatars/{}/{}.png?size=128", user_id, hash)
+            format!("{}/avatars/{}/{}.png?size=128", self.cdn_base, user_id, hash)
         } else {
             let default_num = user_id
                 .chars()
@@ -59,8 +92,66 @@ impl ImageRenderer {
                 .and_then(|c| c.to_digit(10))
                 .unwrap_or(0) % 5;
 
-            format!("https://cdn.discordapp.com/embed/avatars/{}.png", default_num)
+            format!("{}/embed/avatars/{}.png", self.cdn_base, default_num)
+        }
+    }
+
+    pub fn get_sticker_url(&self, sticker_id: &str, extension: &str) -> String {
+        format!("{}/stickers/{}.{}?size=160", self.media_base, sticker_id, extension)
+    }
+
+    pub fn get_emoji_url(&self, emoji_id: &str) -> String {
+        format!("{}/emojis/{}.png?size=64", self.cdn_base, emoji_id)
+    }
+
+    pub fn get_server_icon_url(&self, guild_id: &str, icon_hash: &str) -> String {
+        format!("{}/icons/{}/{}.png?size=64", self.cdn_base, guild_id, icon_hash)
+    }
+
+    /// Load a custom emoji sized to fit two cells, using the `emoji_` disk cache
+    pub async fn load_emoji(&mut self, emoji_id: &str) -> Result<()> {
+        if self.emoji_cache.contains_key(emoji_id) {
+            return Ok(());
+        }
+
+        let key = format!("emoji_{}", emoji_id);
+        let img = self.load_inline_image(&key, &self.get_emoji_url(emoji_id)).await?;
+        let protocol = self.picker.new_resize_protocol(img);
+        self.emoji_cache.insert(emoji_id.to_string(), protocol);
+
+        Ok(())
+    }
+
+    /// Load a server icon sized to fit two cells, using the `icon_` disk cache
+    pub async fn load_server_icon(&mut self, guild_id: &str, icon_hash: &str) -> Result<()> {
+        if self.icon_cache.contains_key(guild_id) {
+            return Ok(());
+        }
+
+        // The hash changes with the icon, so it is part of the key
+        let key = format!("icon_{}_{}", guild_id, icon_hash);
+        let img = self.load_inline_image(&key, &self.get_server_icon_url(guild_id, icon_hash)).await?;
+        let protocol = self.picker.new_resize_protocol(Self::circle_mask(img));
+        self.icon_cache.insert(guild_id.to_string(), protocol);
+
+        Ok(())
+    }
+
+    /// Fetch an image shrunk to two cells wide and one tall, from disk when cached
+    async fn load_inline_image(&self, key: &str, url: &str) -> Result<DynamicImage> {
+        let dir = Self::get_cache_dir()?;
+        let path = dir.join(format!("{}.png", key));
+
+        if path.exists() {
+            let bytes = fs::read(&path).await?;
+            return Ok(image::load_from_memory(&bytes)?);
         }
+
+        let (font_w, font_h) = self.picker.font_size();
+        let img = self.download_image(url).await?;
+        let img = img.resize(font_w as u32 * 2, font_h as u32, FilterType::Lanczos3);
+        img.save(&path)?;
+        Ok(img)
     }
 
     async fn save_processed_avatar_to_disk(&self, key: &str, img: &DynamicImage) -> Result<()> {
@@ -86,7 +177,7 @@ impl ImageRenderer {
             return Ok(());
         }
 
-        let url = Self::get_avatar_url(user_id, avatar_hash);
+        let url = self.get_avatar_url(user_id, avatar_hash);
         let key = format!("avatar_{}", user_id);
 
         let (image, protocol) = if let Ok(img) = self.load_processed_avatar_from_disk(&key).await {
@@ -225,6 +316,8 @@ impl ImageRenderer {
         let mut file_count = 0usize;
         let mut avatar_count = 0usize;
         let mut attachment_count = 0usize;
+        let mut emoji_count = 0usize;
+        let mut icon_count = 0usize;
 
         if let Ok(mut entries) = fs::read_dir(&cache_dir).await {
             while let Ok(Some(entry)) = entries.next_entry().await {
@@ -235,6 +328,10 @@ impl ImageRenderer {
                         if let Some(name) = entry.file_name().to_str() {
                             if name.starts_with("avatar_") {
                                 avatar_count += 1;
+                            } else if name.starts_with("emoji_") {
+                                emoji_count += 1;
+                            } else if name.starts_with("icon_") {
+                                icon_count += 1;
                             } else {
                                 attachment_count += 1;
                             }
@@ -249,6 +346,8 @@ impl ImageRenderer {
             total_files: file_count,
             avatar_files: avatar_count,
             attachment_files: attachment_count,
+            emoji_files: emoji_count,
+            icon_files: icon_count,
             cache_path: cache_dir,
         })
     }
@@ -281,7 +380,7 @@ impl ImageRenderer {
         if let Ok(mut entries) = fs::read_dir(&dir).await {
             while let Ok(Some(entry)) = entries.next_entry().await {
                 if let Some(name) = entry.file_name().to_str() {
-                    if !name.starts_with("avatar_") {
+                    if !["avatar_", "emoji_", "icon_"].iter().any(|prefix| name.starts_with(prefix)) {
                         let _ = fs::remove_file(entry.path()).await;
                     }
                 }
@@ -334,9 +433,27 @@ impl ImageRenderer {
         self.attachment_cache.get(attachment_id).map(|c| c.terminal_width)
     }
 
+    pub fn get_emoji(&mut self, emoji_id: &str) -> Option<&mut StatefulProtocol> {
+        self.emoji_cache.get_mut(emoji_id)
+    }
+
+    pub fn get_server_icon(&mut self, guild_id: &str) -> Option<&mut StatefulProtocol> {
+        self.icon_cache.get_mut(guild_id)
+    }
+
+    pub fn has_server_icon(&self, guild_id: &str) -> bool {
+        self.icon_cache.contains_key(guild_id)
+    }
+
+    pub fn has_emoji(&self, emoji_id: &str) -> bool {
+        self.emoji_cache.contains_key(emoji_id)
+    }
+
     pub fn clear_memory_cache(&mut self) {
         self.avatar_cache.clear();
         self.attachment_cache.clear();
+        self.emoji_cache.clear();
+        self.icon_cache.clear();
     }
 }
 
@@ -346,6 +463,8 @@ pub struct CacheStats {
     pub total_files: usize,
     pub avatar_files: usize,
     pub attachment_files: usize,
+    pub emoji_files: usize,
+    pub icon_files: usize,
     pub cache_path: PathBuf,
 }
 
@@ -0,0 +1,286 @@
+use crate::app::App;
+use crate::ui::highlight;
+use crate::ui::emoji::EmojiSlots;
+use crate::ui::mentions;
+use crate::ui::utils::wrap_spans;
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Render Discord-flavoured markdown into lines wrapped to `width` columns.
+/// Spoilers stay hidden unless `reveal_spoilers` is set. Custom emoji drawn as
+/// images are left as placeholders taken from `emojis`.
+pub fn render(
+    app: &App,
+    content: &str,
+    width: u16,
+    base: Style,
+    reveal_spoilers: bool,
+    emojis: &mut EmojiSlots,
+) -> Vec<Line<'static>> {
+    let theme = app.theme();
+    let width = width.max(1) as usize;
+    let quote_bar = Span::styled("▎ ", Style::default().fg(theme.get_color("base03")));
+
+    let mut lines = Vec::new();
+    let mut in_quote_block = false;
+    let mut source = content.split('\n');
+
+    while let Some(raw) = source.next() {
+        let (quoted, line) = if in_quote_block {
+            (true, raw)
+        } else if let Some(rest) = raw.strip_prefix(">>> ") {
+            in_quote_block = true;
+            (true, rest)
+        } else if let Some(rest) = raw.strip_prefix("> ") {
+            (true, rest)
+        } else {
+            (false, raw)
+        };
+        let quote_prefix: Vec<Span<'static>> = if quoted { vec![quote_bar.clone()] } else { Vec::new() };
+
+        if let Some(fence) = line.trim_start().strip_prefix("```") {
+            let (lang, code) = match fence.strip_suffix("```") {
+                // A fence opened and closed on the same line
+                Some(code) => ("", vec![code.to_string()]),
+                None => {
+                    let mut code = Vec::new();
+                    let mut lang = fence.trim();
+                    if lang.contains(char::is_whitespace) {
+                        code.push(fence.to_string());
+                        lang = "";
+                    }
+                    for next in source.by_ref() {
+                        let next = if in_quote_block { next } else { next.strip_prefix("> ").unwrap_or(next) };
+                        if let Some(before) = next.trim_end().strip_suffix("```") {
+                            if !before.trim().is_empty() {
+                                code.push(before.to_string());
+                            }
+                            break;
+                        }
+                        code.push(next.to_string());
+                    }
+                    (lang, code)
+                }
+            };
+            lines.extend(render_code_block(app, lang, &code, width, &quote_prefix));
+            continue;
+        }
+
+        let (block_style, marker, body) = block_kind(app, line, base);
+        let mut first_prefix = quote_prefix.clone();
+        let mut rest_prefix = quote_prefix;
+        if let Some(marker) = marker {
+            let indent = " ".repeat(marker.chars().count());
+            first_prefix.push(Span::styled(marker, Style::default().fg(theme.get_color("base0E"))));
+            rest_prefix.push(Span::raw(indent));
+        }
+
+        let mut spans = Vec::new();
+        parse_inline(app, body, block_style, reveal_spoilers, emojis, &mut spans);
+        lines.extend(wrap_spans(spans, width, first_prefix, rest_prefix));
+    }
+
+    lines
+}
+
+/// Work out the style, list marker and remaining text for a line
+fn block_kind<'a>(app: &App, line: &'a str, base: Style) -> (Style, Option<String>, &'a str) {
+    let theme = app.theme();
+
+    if let Some(rest) = line.strip_prefix("# ") {
+        return (base.fg(theme.get_color("base0D")).add_modifier(Modifier::BOLD | Modifier::UNDERLINED), None, rest);
+    }
+    if let Some(rest) = line.strip_prefix("## ") {
+        return (base.fg(theme.get_color("base0D")).add_modifier(Modifier::BOLD), None, rest);
+    }
+    if let Some(rest) = line.strip_prefix("### ") {
+        return (base.fg(theme.get_color("base0C")).add_modifier(Modifier::BOLD), None, rest);
+    }
+    if let Some(rest) = line.strip_prefix("-# ") {
+        return (base.fg(theme.get_color("base03")), None, rest);
+    }
+
+    let trimmed = line.trim_start();
+    let indent = " ".repeat((line.len() - trimmed.len()) / 2 * 2);
+
+    if let Some(rest) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+        return (base, Some(format!("{}• ", indent)), rest);
+    }
+    if let Some((number, rest)) = trimmed.split_once(". ") {
+        if !number.is_empty() && number.len() <= 3 && number.bytes().all(|b| b.is_ascii_digit()) {
+            return (base, Some(format!("{}{}. ", indent, number)), rest);
+        }
+    }
+
+    (base, None, line)
+}
+
+/// The text between a pair of `marker`s at the start of `rest`, if it closes
+fn delimited<'a>(rest: &'a str, marker: &str) -> Option<&'a str> {
+    let body = rest.strip_prefix(marker)?;
+    let end = body.find(marker)?;
+    let inner = &body[..end];
+    (!inner.is_empty()).then_some(inner)
+}
+
+/// Parse inline markup, pushing styled spans. Plain runs go through mention resolution.
+fn parse_inline(
+    app: &App,
+    text: &str,
+    style: Style,
+    reveal_spoilers: bool,
+    emojis: &mut EmojiSlots,
+    out: &mut Vec<Span<'static>>,
+) {
+    let theme = app.theme();
+    let mut plain = String::new();
+    let mut i = 0;
+
+    let flush = |plain: &mut String, emojis: &mut EmojiSlots, out: &mut Vec<Span<'static>>| {
+        if !plain.is_empty() {
+            out.extend(mentions::resolve_spans(app, plain, style, emojis));
+            plain.clear();
+        }
+    };
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let prev_is_word = text[..i].chars().next_back().is_some_and(char::is_alphanumeric);
+
+        // Escaped markup characters are kept literally
+        if let Some(escaped) = rest.strip_prefix('\\').and_then(|r| r.chars().next()) {
+            if escaped.is_ascii_punctuation() {
+                plain.push(escaped);
+                i += 1 + escaped.len_utf8();
+                continue;
+            }
+        }
+
+        let code_style = Style::default().fg(theme.get_color("base0C")).bg(theme.get_color("base01"));
+        let code = delimited(rest, "``")
+            .map(|code| (code, 2))
+            .or_else(|| delimited(rest, "`").map(|code| (code, 1)));
+        if let Some((code, marker_len)) = code {
+            flush(&mut plain, emojis, out);
+            out.push(Span::styled(code.to_string(), code_style));
+            i += code.len() + marker_len * 2;
+            continue;
+        }
+
+        let nested = [
+            ("||", None),
+            ("**", Some(Modifier::BOLD)),
+            ("__", Some(Modifier::UNDERLINED)),
+            ("~~", Some(Modifier::CROSSED_OUT)),
+            ("*", Some(Modifier::ITALIC)),
+            ("_", Some(Modifier::ITALIC)),
+        ];
+        let matched = nested.iter().find_map(|(marker, modifier)| {
+            if *marker == "_" && prev_is_word {
+                return None;
+            }
+            let inner = delimited(rest, marker)?;
+            if marker.len() == 1 && inner.starts_with(char::is_whitespace) {
+                return None;
+            }
+            Some((marker.len(), *modifier, inner))
+        });
+
+        if let Some((marker_len, modifier, inner)) = matched {
+            flush(&mut plain, emojis, out);
+            match modifier {
+                Some(modifier) => parse_inline(app, inner, style.add_modifier(modifier), reveal_spoilers, emojis, out),
+                None if reveal_spoilers => {
+                    parse_inline(app, inner, style.bg(theme.get_color("base02")), reveal_spoilers, emojis, out)
+                }
+                // Hidden spoilers skip inline parsing so nothing inside can show through
+                None => out.push(Span::styled(
+                    inner.to_string(),
+                    style.fg(theme.get_color("base02")).bg(theme.get_color("base02")),
+                )),
+            }
+            i += inner.len() + marker_len * 2;
+            continue;
+        }
+
+        let link_style = style.fg(theme.get_color("base0D")).add_modifier(Modifier::UNDERLINED);
+
+        // Masked links: [text](https://...)
+        if let Some((label, after)) = rest.strip_prefix('[').and_then(|r| r.split_once("](")) {
+            if let Some((url, _)) = after.split_once(')') {
+                if !label.is_empty() && !label.contains(']') && url.starts_with("http") && !url.contains(' ') {
+                    flush(&mut plain, emojis, out);
+                    parse_inline(app, label, link_style, reveal_spoilers, emojis, out);
+                    i += 1 + label.len() + 2 + url.len() + 1;
+                    continue;
+                }
+            }
+        }
+
+        // Bare links
+        if !prev_is_word && (rest.starts_with("https://") || rest.starts_with("http://")) {
+            flush(&mut plain, emojis, out);
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            out.push(Span::styled(rest[..end].to_string(), link_style));
+            i += end;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap();
+        plain.push(c);
+        i += c.len_utf8();
+    }
+
+    flush(&mut plain, emojis, out);
+}
+
+fn render_code_block(
+    app: &App,
+    lang: &str,
+    code: &[String],
+    width: usize,
+    quote_prefix: &[Span<'static>],
+) -> Vec<Line<'static>> {
+    let theme = app.theme();
+    let border = Style::default().fg(theme.get_color("base03"));
+    let background = Style::default().bg(theme.get_color("base01"));
+
+    let mut prefix = quote_prefix.to_vec();
+    prefix.push(Span::styled("│ ", border));
+    let edge = |corner: &str, label: &str| {
+        let mut spans = quote_prefix.to_vec();
+        spans.push(Span::styled(format!("{}─ {}", corner, label), border));
+        Line::from(spans)
+    };
+
+    let highlighted = highlight::highlight(lang, code);
+
+    let mut lines = vec![edge("╭", lang)];
+    for (i, line) in code.iter().enumerate() {
+        let spans: Vec<Span<'static>> = match highlighted.as_ref().and_then(|h| h.get(i)) {
+            Some(tokens) => tokens
+                .iter()
+                .map(|token| {
+                    let mut style = background.fg(theme.get_color(token.slot.unwrap_or("base05")));
+                    if token.italic {
+                        style = style.add_modifier(Modifier::ITALIC);
+                    }
+                    Span::styled(token.text.clone(), style)
+                })
+                .collect(),
+            None => vec![Span::styled(line.replace('\t', "    "), background.fg(theme.get_color("base05")))],
+        };
+
+        // Pad each row so the background fills the block
+        for mut row in wrap_spans(spans, width, prefix.clone(), prefix.clone()) {
+            let padding = width.saturating_sub(row.width());
+            row.push_span(Span::styled(" ".repeat(padding), background));
+            lines.push(row);
+        }
+    }
+    lines.push(edge("╰", ""));
+
+    lines
+}
@@ -0,0 +1,103 @@
+use crate::app::App;
+use crate::models::mention::{find_mentions, split_mentions, Mention, Segment};
+use crate::ui::emoji::EmojiSlots;
+use crate::ui::utils::format_relative_time;
+use chrono::{DateTime, Local};
+use ratatui::{
+    style::{Modifier, Style},
+    text::Span,
+};
+
+/// Emoji slots for everything drawn from `texts`, kept clear of the names their
+/// mentions resolve to as well, since those only turn up after placeholders are handed out
+pub fn emoji_slots<'a>(app: &App, texts: impl IntoIterator<Item = &'a str>) -> EmojiSlots {
+    let mut slots = EmojiSlots::default();
+    for text in texts {
+        slots.reserve(text);
+        for mention in find_mentions(text) {
+            slots.reserve(&mention_label(app, &mention));
+        }
+    }
+    slots
+}
+
+/// Split a line of message text into spans, resolving mentions to names.
+/// Custom emoji that can be drawn as images become placeholders taken from `emojis`,
+/// which should come from [`emoji_slots`].
+pub fn resolve_spans(app: &App, text: &str, base: Style, emojis: &mut EmojiSlots) -> Vec<Span<'static>> {
+    let theme = app.theme();
+    let emoji_images = app.config.images.enabled
+        && app.config.images.render_emojis
+        && app.image_renderer.is_supported();
+
+    split_mentions(text)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => Span::styled(text.to_string(), base),
+            Segment::Mention(Mention::Emoji(emoji)) => {
+                let id = emoji.id.clone().unwrap_or_default();
+                let placeholder = (emoji_images && app.image_renderer.has_emoji(&id))
+                    .then(|| emojis.push(id))
+                    .flatten();
+                match placeholder {
+                    Some(placeholder) => Span::styled(placeholder, base),
+                    None => Span::styled(emoji.label(), base.fg(theme.get_color("base0A"))),
+                }
+            }
+            Segment::Mention(mention) => {
+                let color = match &mention {
+                    Mention::User(_) => "base0D",
+                    Mention::Channel(_) => "base0C",
+                    Mention::Role(_) => "base0E",
+                    Mention::Timestamp(..) => "base09",
+                    Mention::Emoji(_) => "base0A",
+                };
+                let label = mention_label(app, &mention);
+                let mut style = base.fg(theme.get_color(color));
+                if !matches!(mention, Mention::Timestamp(..)) {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                Span::styled(label, style)
+            }
+        })
+        .collect()
+}
+
+/// Message text with mentions resolved to names, for places that cannot draw spans
+pub fn resolve_plain(app: &App, text: &str) -> String {
+    split_mentions(text)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text.to_string(),
+            Segment::Mention(mention) => mention_label(app, &mention),
+        })
+        .collect()
+}
+
+fn mention_label(app: &App, mention: &Mention) -> String {
+    match mention {
+        Mention::User(id) => format!("@{}", app.user_name(id).unwrap_or("unknown-user")),
+        Mention::Channel(id) => format!("#{}", app.channel_name(id).unwrap_or("unknown-channel")),
+        Mention::Role(id) => format!("@{}", app.role_name(id).unwrap_or("unknown-role")),
+        Mention::Timestamp(secs, style) => format_timestamp(*secs, *style),
+        Mention::Emoji(emoji) => emoji.label(),
+    }
+}
+
+/// Render a `<t:...>` timestamp in local time using Discord's format letters
+fn format_timestamp(secs: i64, style: char) -> String {
+    let Some(time) = DateTime::from_timestamp(secs, 0) else {
+        return format!("<t:{}>", secs);
+    };
+    let time = time.with_timezone(&Local);
+
+    match style {
+        't' => time.format("%H:%M").to_string(),
+        'T' => time.format("%H:%M:%S").to_string(),
+        'd' => time.format("%d/%m/%Y").to_string(),
+        'D' => time.format("%B %-d, %Y").to_string(),
+        'F' => time.format("%A, %B %-d, %Y %H:%M").to_string(),
+        'R' => format_relative_time(secs * 1000),
+        _ => time.format("%B %-d, %Y %H:%M").to_string(),
+    }
+}
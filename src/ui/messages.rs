@@ -1,9 +1,11 @@
 use crate::app::{App, AppMode};
+use crate::models::{Message, SendState};
+use crate::ui::{embed::{self, EmbedLayout}, emoji::{self, EmojiSlots}, markdown, mentions, utils::format_modifiers};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 use ratatui_image::StatefulImage;
@@ -45,28 +47,19 @@ fn draw_header(f: &mut Frame, app: &App, area: Rect) {
 
 fn draw_messages_area(f: &mut Frame, app: &mut App, area: Rect) {
     let theme = app.theme();
-    let show_avatars = app.config.images.enabled && app.config.images.render_avatars;
-    let show_attachments = app.config.images.enabled && app.config.images.render_attachments;
-
-    let author_color = theme.get_color("base0E");
     let time_color = theme.get_color("base03");
-    let text_color = theme.get_color("base05");
-    let attachment_color = theme.get_color("base0C");
-    let dim_color = theme.get_color("base04");
     let border_color = theme.get_color("base03");
 
-    let mut typing_line = Vec::new();
-    if app.config.general.show_typing_indicators && !app.typing_users.is_empty() {
-        let typing_text = if app.typing_users.len() == 1 {
-            format!("{} is typing...", app.typing_users[0])
-        } else {
-            format!("{} are typing...", app.typing_users.join(", "))
-        };
-        typing_line.push(Line::from(vec![
-            Span::styled(typing_text, Style::default().fg(time_color).add_modifier(Modifier::ITALIC)),
-        ]));
-        typing_line.push(Line::from(""));
-    }
+    let typing_names = app.current_typing_names();
+    let typing_text = if !app.config.general.show_typing_indicators || typing_names.is_empty() {
+        None
+    } else if typing_names.len() == 1 {
+        Some(format!("{} is typing...", typing_names[0]))
+    } else if typing_names.len() <= 3 {
+        Some(format!("{} are typing...", typing_names.join(", ")))
+    } else {
+        Some("Several people are typing...".to_string())
+    };
 
     let messages_inner = Block::default()
         .borders(Borders::ALL)
@@ -74,21 +67,137 @@ fn draw_messages_area(f: &mut Frame, app: &mut App, area: Rect) {
         .border_style(Style::default().fg(border_color))
         .inner(area);
 
+    // Keep the last row free for the typing indicator
+    let messages_bottom = if typing_text.is_some() {
+        messages_inner.bottom().saturating_sub(1)
+    } else {
+        messages_inner.bottom()
+    };
+
     let mut current_y = messages_inner.y;
-    let messages_to_show = app
+
+    if app.message_scroll == 0 && messages_inner.height > 2 {
+        let channel_id = app.selected_channel.as_deref().unwrap_or_default();
+        let history_text = if app.loading_older_messages {
+            Some("Loading older messages...".to_string())
+        } else if app.is_history_exhausted(channel_id) {
+            let name = app.get_current_channel_name().unwrap_or_else(|| "this channel".to_string());
+            Some(format!("This is the start of {}", name))
+        } else {
+            None
+        };
+
+        if let Some(text) = history_text {
+            let history_area = Rect {
+                x: messages_inner.x,
+                y: current_y,
+                width: messages_inner.width,
+                height: 1,
+            };
+            f.render_widget(
+                Paragraph::new(Span::styled(text, Style::default().fg(time_color).add_modifier(Modifier::ITALIC))),
+                history_area,
+            );
+            current_y += 2;
+        }
+    }
+
+    // Each message takes at least MIN_MESSAGE_HEIGHT rows, so this is all that can fit
+    let visible: Vec<Message> = app
         .messages
         .iter()
         .skip(app.message_scroll)
-        .collect::<Vec<_>>();
+        .take(messages_inner.height as usize / MIN_MESSAGE_HEIGHT as usize + 1)
+        .cloned()
+        .collect();
+    let selected = (app.mode == AppMode::Messages).then_some(0);
+    let numbered = (app.mode == AppMode::Reaction).then_some(0);
+    draw_message_list(f, app, messages_inner, current_y, messages_bottom, &visible, selected, numbered);
+
+    if let Some(text) = typing_text {
+        let typing_area = Rect {
+            x: messages_inner.x,
+            y: messages_bottom,
+            width: messages_inner.width,
+            height: 1,
+        };
+        f.render_widget(Clear, typing_area);
+        f.render_widget(
+            Paragraph::new(Span::styled(text, Style::default().fg(time_color).add_modifier(Modifier::ITALIC))),
+            typing_area,
+        );
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Messages")
+        .border_style(Style::default().fg(border_color));
+    f.render_widget(block, area);
+}
+
+/// Draw `messages` from row `y` down to `messages_bottom` inside `messages_inner`.
+/// `selected` is marked with `›` and `numbered` gets numbered reactions.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_message_list(
+    f: &mut Frame,
+    app: &mut App,
+    messages_inner: Rect,
+    y: u16,
+    messages_bottom: u16,
+    messages: &[Message],
+    selected: Option<usize>,
+    numbered: Option<usize>,
+) {
+    let theme = app.theme();
+    let show_avatars = app.config.images.enabled && app.config.images.render_avatars;
+    let show_attachments = app.config.images.enabled && app.config.images.render_attachments;
+    let show_stickers = app.config.images.enabled && app.config.images.render_stickers;
+    let emoji_images = app.config.images.enabled
+        && app.config.images.render_emojis
+        && app.image_renderer.is_supported();
 
-    for msg in messages_to_show {
-        if current_y >= messages_inner.bottom() {
+    let author_color = theme.get_color("base0E");
+    let time_color = theme.get_color("base03");
+    let text_color = theme.get_color("base05");
+    let attachment_color = theme.get_color("base0C");
+    let dim_color = theme.get_color("base04");
+    let border_color = theme.get_color("base03");
+    let selected_color = theme.get_color("base0A");
+    let reaction_color = theme.get_color("base0B");
+    let error_color = theme.get_color("base08");
+
+    let mut current_y = y;
+
+    let text_width = if show_avatars {
+        messages_inner.width.saturating_sub(AVATAR_DISPLAY_WIDTH)
+    } else {
+        messages_inner.width
+    };
+
+    for (idx, msg) in messages.iter().enumerate() {
+        if current_y >= messages_bottom {
             break;
         }
 
-        let content_lines = if msg.content.is_empty() { 0 } else { msg.content.lines().count() };
-        let header_height = 1;
-        let content_height = if content_lines > 0 { content_lines + 1 } else { 0 };
+        let is_selected = selected == Some(idx);
+        let mut emoji_slots = mentions::emoji_slots(app, [msg.content.as_str()]);
+        let mut content_lines = if msg.content.is_empty() {
+            Vec::new()
+        } else {
+            // Messages still in the outbox are dimmed until Discord has them
+            let content_color = if msg.send_state.is_some() { time_color } else { text_color };
+            markdown::render(app, &msg.content, text_width, Style::default().fg(content_color), is_selected, &mut emoji_slots)
+        };
+        let inline_emojis = emoji::take_placeholders(&mut content_lines, &emoji_slots);
+        if msg.edited && !content_lines.is_empty() {
+            let edited = Span::styled(" (edited)", Style::default().fg(time_color));
+            match content_lines.last_mut() {
+                Some(last) if last.width() + edited.width() <= text_width as usize => last.push_span(edited),
+                _ => content_lines.push(Line::from(edited)),
+            }
+        }
+        let header_height = if msg.reply_to.is_some() { 2 } else { 1 };
+        let content_height = if content_lines.is_empty() { 0 } else { content_lines.len() + 1 };
         
         let mut attachment_text_height = 0;
         let mut image_height = 0;
@@ -111,10 +220,25 @@ fn draw_messages_area(f: &mut Frame, app: &mut App, area: Rect) {
             attachment_text_height += 1;
         }
         
-        let text_total_height = header_height + content_height + attachment_text_height;
+        let embed_layouts: Vec<EmbedLayout> = msg.embeds
+            .iter()
+            .enumerate()
+            .map(|(i, e)| embed::layout(app, &msg.id, i, e, text_width))
+            .collect();
+        let embed_height: usize = embed_layouts.iter().map(|l| l.height() as usize).sum();
+        
+        for sticker in &msg.stickers {
+            match app.image_renderer.get_attachment_height(&sticker.cache_key()).filter(|_| show_stickers) {
+                Some(height) => image_height += height as usize,
+                None => attachment_text_height += 1,
+            }
+        }
+        
+        let reaction_height = if msg.reactions.is_empty() { 0 } else { 1 };
+        let text_total_height = header_height + content_height + attachment_text_height + embed_height + reaction_height;
         let calculated_height = text_total_height + image_height;
         let total_height = calculated_height.max(MIN_MESSAGE_HEIGHT as usize);
-        let remaining_height = messages_inner.bottom().saturating_sub(current_y);
+        let remaining_height = messages_bottom.saturating_sub(current_y);
         let msg_height = (total_height.min(remaining_height as usize) as u16).max(MIN_MESSAGE_HEIGHT);
 
         let msg_chunks = if show_avatars {
@@ -159,20 +283,59 @@ fn draw_messages_area(f: &mut Frame, app: &mut App, area: Rect) {
         let time_style = Style::default().fg(time_color);
 
         let mut header_spans = vec![];
+        if is_selected {
+            header_spans.push(Span::styled("› ", Style::default().fg(selected_color)));
+        }
         if app.config.general.show_timestamps {
             header_spans.push(Span::styled(format!("[{}] ", msg.timestamp), time_style));
         }
         header_spans.push(Span::styled(&msg.author, author_style));
+        if msg.pinned {
+            header_spans.push(Span::styled(" (pinned)", time_style));
+        }
+        match &msg.send_state {
+            Some(SendState::Queued) => header_spans.push(Span::styled(" (queued)", time_style)),
+            Some(SendState::Sending) => header_spans.push(Span::styled(" (sending...)", time_style)),
+            Some(SendState::Failed(_)) => header_spans.push(Span::styled(
+                format!(
+                    " failed to send, {} to retry, {} to discard",
+                    app.config.keybinds.retry_send.key, app.config.keybinds.delete_message.key
+                ),
+                Style::default().fg(error_color),
+            )),
+            None => {}
+        }
 
-        let mut message_lines = vec![Line::from(header_spans)];
+        let mut message_lines = Vec::new();
+
+        if let Some(reply) = &msg.reply_to {
+            let quote_style = Style::default().fg(time_color).add_modifier(Modifier::ITALIC);
+            let reply_line = if reply.is_available() {
+                let author = format!("@{} ", reply.author);
+                // header_height counts the reply as one row, so it must not wrap
+                let snippet_width = (text_width as usize).saturating_sub(3 + unicode_width::UnicodeWidthStr::width(author.as_str()));
+                Line::from(vec![
+                    Span::styled("╭─ ", Style::default().fg(border_color)),
+                    Span::styled(author, Style::default().fg(author_color)),
+                    Span::styled(reply.snippet(snippet_width), quote_style),
+                ])
+            } else {
+                Line::from(vec![
+                    Span::styled("╭─ ", Style::default().fg(border_color)),
+                    Span::styled("Original message was deleted", quote_style),
+                ])
+            };
+            message_lines.push(reply_line);
+        }
 
-        if !msg.content.is_empty() {
-            for line in msg.content.lines() {
-                message_lines.push(Line::from(Span::styled(line.to_string(), Style::default().fg(text_color))));
-            }
+        message_lines.push(Line::from(header_spans));
+
+        if !content_lines.is_empty() {
+            message_lines.extend(content_lines);
             message_lines.push(Line::from(""));
         }
 
+        let content_top = header_height as u16;
         let text_height = message_lines.len() as u16;
         let text_area = Rect {
             x: message_area.x,
@@ -184,6 +347,8 @@ fn draw_messages_area(f: &mut Frame, app: &mut App, area: Rect) {
         let message_para = Paragraph::new(message_lines).wrap(Wrap { trim: false });
         f.render_widget(message_para, text_area);
 
+        emoji::draw(f, &mut app.image_renderer, text_area, content_top, &inline_emojis);
+
         let mut attachment_y = text_area.y + text_height;
 
         if !msg.attachments.is_empty() {
@@ -259,31 +424,139 @@ fn draw_messages_area(f: &mut Frame, app: &mut App, area: Rect) {
             }
         }
 
+        for sticker in &msg.stickers {
+            if attachment_y >= message_area.bottom() {
+                break;
+            }
+
+            let key = sticker.cache_key();
+            let size = app.image_renderer.get_attachment_width(&key)
+                .zip(app.image_renderer.get_attachment_height(&key))
+                .filter(|_| show_stickers);
+
+            if let Some((img_width, img_height)) = size {
+                if let Some(mut protocol) = app.image_renderer.get_attachment(&key) {
+                    let img_area = Rect {
+                        x: message_area.x,
+                        y: attachment_y,
+                        width: img_width.min(message_area.width),
+                        height: img_height.min(message_area.bottom() - attachment_y),
+                    };
+                    f.render_stateful_widget(StatefulImage::default(), img_area, &mut protocol);
+                    attachment_y += img_height;
+                    continue;
+                }
+            }
+
+            let label = if sticker.image_extension().is_none() {
+                format!("s  {} (animated sticker)", sticker.name)
+            } else if show_stickers {
+                format!("s  {}", sticker.name)
+            } else {
+                format!("s  {} (stickers disabled)", sticker.name)
+            };
+            let label_area = Rect {
+                x: message_area.x,
+                y: attachment_y,
+                width: message_area.width,
+                height: 1,
+            };
+            f.render_widget(Paragraph::new(Span::styled(label, Style::default().fg(dim_color))), label_area);
+            attachment_y += 1;
+        }
+
+        for layout in &embed_layouts {
+            if attachment_y >= message_area.bottom() {
+                break;
+            }
+            embed::draw(f, &mut app.image_renderer, layout, message_area.x, attachment_y, message_area.bottom());
+            attachment_y += layout.height();
+        }
+
+        if !msg.reactions.is_empty() && attachment_y < message_area.bottom() {
+            let numbered = numbered == Some(idx);
+            let mut reaction_spans = Vec::new();
+            let mut reaction_slots = EmojiSlots::new(msg.reactions.iter().map(|r| r.emoji.name.as_str()));
+
+            for (i, reaction) in msg.reactions.iter().enumerate() {
+                let style = if reaction.me {
+                    Style::default().fg(reaction_color).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(dim_color)
+                };
+                // Custom emoji fall back to `:name:` when they cannot be drawn as images
+                let emoji = reaction.emoji.id.clone()
+                    .filter(|id| emoji_images && app.image_renderer.has_emoji(id))
+                    .and_then(|id| reaction_slots.push(id))
+                    .unwrap_or_else(|| reaction.emoji.label());
+                let label = if numbered {
+                    format!("[{} {} {}]", i + 1, emoji, reaction.count)
+                } else {
+                    format!("[{} {}]", emoji, reaction.count)
+                };
+                reaction_spans.push(Span::styled(label, style));
+                reaction_spans.push(Span::raw(" "));
+            }
+
+            let reaction_area = Rect {
+                x: message_area.x,
+                y: attachment_y,
+                width: message_area.width,
+                height: 1,
+            };
+            let mut reaction_lines = vec![Line::from(reaction_spans)];
+            let reaction_emojis = emoji::take_placeholders(&mut reaction_lines, &reaction_slots);
+            f.render_widget(Paragraph::new(reaction_lines), reaction_area);
+            emoji::draw(f, &mut app.image_renderer, reaction_area, 0, &reaction_emojis);
+        }
+
         current_y += msg_height;
     }
-
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title("Messages")
-        .border_style(Style::default().fg(border_color));
-    f.render_widget(block, area);
 }
 
 fn draw_input(f: &mut Frame, app: &App, area: Rect) {
     let theme = app.theme();
 
+    if app.mode == AppMode::Reaction {
+        let prompt = Paragraph::new(app.reaction_input.as_str())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("React (number, :name: or emoji; Enter to toggle, Esc to cancel)")
+                    .border_style(Style::default().fg(theme.get_color("base0A"))),
+            )
+            .style(Style::default().fg(theme.get_color("base05")));
+        f.render_widget(prompt, area);
+
+        let cursor_x = area.x + 1 + unicode_width::UnicodeWidthStr::width(app.reaction_input.as_str()) as u16;
+        f.set_cursor_position((cursor_x.min(area.right().saturating_sub(2)), area.y + 1));
+        return;
+    }
+
     let input_style = if app.mode == AppMode::Input {
         Style::default().fg(theme.get_color("base0A"))
     } else {
         Style::default().fg(theme.get_color("base03"))
     };
 
-    let mut input_title = if app.mode == AppMode::Input {
+    let mut input_title = if app.mode == AppMode::Input && app.editing_message.is_some() {
+        format!("Editing message ({} to save, {} to cancel)", app.config.keybinds.send_message.key, app.config.keybinds.cancel_input.key)
+    } else if app.mode == AppMode::Input {
         format!("Input ({} to send, {} to cancel)", app.config.keybinds.send_message.key, app.config.keybinds.cancel_input.key)
     } else {
         format!("Input (press '{}' to type, '{}' to attach file)", app.config.keybinds.input_mode.key, app.config.keybinds.attach_file.key)
     };
 
+    if let Some(reply) = &app.replying_to {
+        input_title.push_str(&format!(
+            " - Replying to {} (ping {}, {}{} to toggle)",
+            reply.author,
+            if reply.mention { "on" } else { "off" },
+            format_modifiers(&app.config.keybinds.toggle_reply_mention.modifiers),
+            app.config.keybinds.toggle_reply_mention.key,
+        ));
+    }
+
     if !app.attached_files.is_empty() {
         input_title.push_str(&format!(" [{} file(s)]", app.attached_files.len()));
     }
@@ -2,6 +2,16 @@ mod sidebar;
 mod messages;
 mod settings;
 mod help;
+mod forum;
+mod pins;
+mod accounts;
+mod token;
+mod search;
+mod embed;
+mod emoji;
+mod highlight;
+mod markdown;
+pub mod mentions;
 mod utils;
 pub mod image;
 pub mod notifications;
@@ -27,8 +37,30 @@ pub fn draw(f: &mut Frame, app: &mut App) {
                 AppMode::Sidebar => {
                     help::draw(f, app, main_chunks[1]);
                 }
-                AppMode::Messages | AppMode::Input => {
+                AppMode::Accounts | AppMode::TokenSetup => {
+                    // Over the open channel, or the bare sidebar
+                    if app.selected_channel.is_some() {
+                        messages::draw(f, app, main_chunks[1]);
+                    }
+                    if app.mode == AppMode::Accounts {
+                        accounts::draw(f, app, f.area());
+                    } else {
+                        token::draw(f, app, f.area());
+                    }
+                }
+                AppMode::Messages | AppMode::Input | AppMode::Reaction => {
+                    messages::draw(f, app, main_chunks[1]);
+                }
+                AppMode::Pins => {
                     messages::draw(f, app, main_chunks[1]);
+                    pins::draw(f, app, main_chunks[1]);
+                }
+                AppMode::Search => {
+                    messages::draw(f, app, main_chunks[1]);
+                    search::draw(f, app, main_chunks[1]);
+                }
+                AppMode::Forum | AppMode::ForumCompose => {
+                    forum::draw(f, app, main_chunks[1]);
                 }
                 _ => {}
             }
@@ -0,0 +1,49 @@
+use crate::app::App;
+use crate::models::Message;
+use crate::ui::messages::draw_message_list;
+use crate::ui::utils::centered_rect;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme();
+    let kb = &app.config.keybinds;
+    let pins: Vec<Message> = app.current_pins().to_vec();
+
+    let popup = centered_rect(90, 80, area);
+    f.render_widget(Clear, popup);
+
+    let mut title = format!("Pinned Messages ({})", pins.len());
+    if app.selected_channel.as_deref().is_some_and(|id| app.can_pin(id)) {
+        title.push_str(&format!(" - {}: unpin", kb.toggle_pin.key));
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom(format!(" {}: jump to message, {}: close ", kb.select.key, kb.back.key))
+        .border_style(Style::default().fg(theme.get_color("base0A")));
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    if pins.is_empty() {
+        let text = if app.loading_pins || !app.selected_channel.as_ref().is_some_and(|id| app.pins.contains_key(id)) {
+            "Loading pinned messages..."
+        } else {
+            "This channel has no pinned messages"
+        };
+        f.render_widget(
+            Paragraph::new(Span::styled(text, Style::default().fg(theme.get_color("base03")).add_modifier(Modifier::ITALIC))),
+            inner,
+        );
+        return;
+    }
+
+    // Keep the selected pin at the top, like the message view
+    let visible = &pins[app.pins_selected_idx.min(pins.len() - 1)..];
+    draw_message_list(f, app, inner, inner.y, inner.bottom(), visible, Some(0), None);
+}
@@ -0,0 +1,132 @@
+use crate::app::App;
+use crate::models::SearchScope;
+use crate::ui::mentions;
+use crate::ui::utils::centered_rect;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+// Each hit takes a context line and a content line
+const RESULT_HEIGHT: usize = 2;
+
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let Some(search) = &app.search else {
+        return;
+    };
+    let theme = app.theme();
+    let kb = &app.config.keybinds;
+
+    let popup = centered_rect(80, 80, area);
+    f.render_widget(Clear, popup);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(popup);
+
+    let scope_name = match &search.scope {
+        SearchScope::Guild(id) => app.guilds.iter().find(|g| &g.id == id).map(|g| g.name.clone()),
+        SearchScope::Channel(id) => app.dms.iter().find(|dm| &dm.id == id).map(|dm| dm.display_name()),
+    }
+    .unwrap_or_else(|| "Unknown".to_string());
+
+    let input = Paragraph::new(format!("{}_", search.input))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Search {} (from: in: has: before: after:)", scope_name))
+                .border_style(Style::default().fg(theme.get_color("base0A"))),
+        )
+        .style(Style::default().fg(theme.get_color("base05")));
+    f.render_widget(input, chunks[0]);
+
+    let title = if search.query.is_some() && !search.loading {
+        format!(
+            "Results: {} (page {} of {})",
+            search.total_results,
+            search.page + 1,
+            search.page_count().max(1)
+        )
+    } else {
+        "Results".to_string()
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(theme.get_color("base03")));
+
+    if search.loading || search.results.is_empty() {
+        let text = if search.loading {
+            "Searching..."
+        } else if search.query.is_some() {
+            "No messages found"
+        } else {
+            "Type a query and press Enter"
+        };
+        let empty = Paragraph::new(text)
+            .block(block)
+            .style(Style::default().fg(theme.get_color("base04")));
+        f.render_widget(empty, chunks[1]);
+    } else {
+        let visible = (chunks[1].height.saturating_sub(2) as usize / RESULT_HEIGHT).max(1);
+        let start = search.selected_idx.saturating_sub(visible - 1);
+        let content_width = chunks[1].width.saturating_sub(4) as usize;
+
+        let items: Vec<ListItem> = search.results
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(visible)
+            .map(|(i, msg)| {
+                let is_selected = i == search.selected_idx;
+                let marker = if is_selected { "› " } else { "  " };
+                let channel = app
+                    .channel_name(&msg.channel_id)
+                    .map(|name| format!("#{}", name))
+                    .unwrap_or_else(|| scope_name.clone());
+
+                let mut author_style = Style::default().fg(theme.get_color("base0E")).add_modifier(Modifier::BOLD);
+                if is_selected {
+                    author_style = author_style.fg(theme.get_color("base0A"));
+                }
+                let context = Line::from(vec![
+                    Span::styled(marker, Style::default().fg(theme.get_color("base0A"))),
+                    Span::styled(format!("{} ", channel), Style::default().fg(theme.get_color("base0C"))),
+                    Span::styled(msg.author.clone(), author_style),
+                    Span::styled(format!(" [{}]", msg.timestamp), Style::default().fg(theme.get_color("base03"))),
+                ]);
+
+                let content = if msg.content.is_empty() && !msg.attachments.is_empty() {
+                    format!("[{} attachment(s)]", msg.attachments.len())
+                } else {
+                    mentions::resolve_plain(app, msg.content.lines().next().unwrap_or(""))
+                };
+                let snippet: String = content.chars().take(content_width).collect();
+
+                ListItem::new(vec![
+                    context,
+                    Line::from(Span::styled(format!("  {}", snippet), Style::default().fg(theme.get_color("base05")))),
+                ])
+            })
+            .collect();
+
+        f.render_widget(List::new(items).block(block), chunks[1]);
+    }
+
+    let hint = format!(
+        " {}: search / jump to result  Up/Down: select  Tab/Shift+Tab: page  {}: close",
+        kb.select.key, kb.cancel_input.key
+    );
+    f.render_widget(
+        Paragraph::new(hint).style(Style::default().fg(theme.get_color("base04"))),
+        chunks[2],
+    );
+}
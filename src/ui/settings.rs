@@ -5,7 +5,7 @@ use ratatui::{
     Frame,
 };
 
-use super::utils::centered_rect;
+use super::utils::{centered_rect, format_modifiers};
 
 pub fn draw(f: &mut Frame, app: &App) {
     let theme = app.theme();
@@ -52,10 +52,12 @@ pub fn draw(f: &mut Frame, app: &App) {
             stats.total_size_mb() as usize, 
             max_size_mb, 
             usage_percent));
-        settings_items.push(format!("  Files: {} total ({} avatars, {} attachments)", 
+        settings_items.push(format!("  Files: {} total ({} avatars, {} attachments, {} emojis, {} server icons)", 
             stats.total_files,
             stats.avatar_files,
-            stats.attachment_files));
+            stats.attachment_files,
+            stats.emoji_files,
+            stats.icon_files));
     } else {
         settings_items.push("  Current Usage: Loading...".to_string());
     }
@@ -123,11 +125,3 @@ pub fn draw(f: &mut Frame, app: &App) {
 
     f.render_widget(list, area);
 }
-
-fn format_modifiers(modifiers: &[String]) -> String {
-    if modifiers.is_empty() {
-        String::new()
-    } else {
-        format!("{}+", modifiers.join("+"))
-    }
-}
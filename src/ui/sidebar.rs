@@ -1,10 +1,19 @@
-use crate::app::{App, AppMode, SidebarItem};
+use crate::app::{App, AppMode, SidebarItem, UnreadCount};
+use crate::discord::ConnectionState;
+use crate::models::{Channel, Guild};
 use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListItem},
     Frame,
 };
+use ratatui_image::StatefulImage;
+use unicode_width::UnicodeWidthChar;
+
+// Server rows are "  ▼ " followed by a two-cell icon
+const SERVER_ICON_OFFSET: u16 = 4;
+const SERVER_ICON_WIDTH: u16 = 2;
 
 pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     let items = app.get_sidebar_items();
@@ -18,6 +27,9 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     let items = &items[start_idx..end_idx];
 
     let theme = app.theme();
+    let icon_images = app.config.images.enabled
+        && app.config.images.render_server_icons
+        && app.image_renderer.is_supported();
     
     let list_items: Vec<ListItem> = items
         .iter()
@@ -38,7 +50,7 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
                 }
                 SidebarItem::Server(guild) => {
                     let arrow = if guild.expanded { "▼ " } else { "▶ " };
-                    (format!("  {}{}", arrow, guild.name), 1)
+                    (format!("  {}", arrow), 1)
                 }
                 SidebarItem::Category { category, expanded, .. } => {
                     let arrow = if *expanded { "▼ " } else { "▶ " };
@@ -52,12 +64,20 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
                     };
                     (format!("{}{}{}", indent, channel.prefix(), channel.name), 3)
                 }
+                SidebarItem::Thread { thread, .. } => {
+                    let indent = if is_categorized_thread(app, thread) {
+                        "        "
+                    } else {
+                        "      "
+                    };
+                    (format!("{}{}{}", indent, thread.prefix(), thread.name), 4)
+                }
             };
 
             let is_selected = i == app.selected_sidebar_idx;
             let is_active = match item {
                 SidebarItem::DmChannel(dm) => Some(&dm.id) == app.selected_channel.as_ref(),
-                SidebarItem::Channel { channel, .. } => {
+                SidebarItem::Channel { channel, .. } | SidebarItem::Thread { thread: channel, .. } => {
                     Some(&channel.id) == app.selected_channel.as_ref()
                 }
                 _ => false,
@@ -70,6 +90,27 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
             
             let is_category = matches!(item, SidebarItem::Category { .. });
 
+            // Collapsed sections and categories carry the counts of what they hide
+            let unread = match item {
+                SidebarItem::DmSection if !app.dm_section_expanded => app.dm_unread(),
+                SidebarItem::DmChannel(dm) => app.read_state.count(&dm.id),
+                SidebarItem::Server(guild) => app.guild_unread(&guild.id),
+                SidebarItem::Category { guild_id, category, expanded: false } => {
+                    app.category_unread(guild_id, &category.id)
+                }
+                SidebarItem::Channel { guild_id, channel } => app.channel_unread(guild_id, channel),
+                SidebarItem::Thread { thread, .. } => app.read_state.count(&thread.id),
+                _ => UnreadCount::default(),
+            };
+
+            let is_muted = match item {
+                SidebarItem::DmChannel(dm) => app.is_channel_muted(&dm.id),
+                SidebarItem::Channel { channel, .. } | SidebarItem::Thread { thread: channel, .. } => {
+                    app.is_channel_muted(&channel.id)
+                }
+                _ => false,
+            };
+
             let style = if is_section && is_selected {
                 Style::default()
                     .fg(theme.get_color("base0D"))
@@ -78,6 +119,12 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
                 Style::default()
                     .fg(theme.get_color("base0A"))
                     .add_modifier(Modifier::BOLD)
+            } else if is_muted {
+                Style::default().fg(theme.get_color("base03"))
+            } else if unread.is_unread() {
+                Style::default()
+                    .fg(theme.get_color("base07"))
+                    .add_modifier(Modifier::BOLD)
             } else if is_category {
                 Style::default()
                     .fg(theme.get_color("base04"))
@@ -90,7 +137,23 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
                 Style::default().fg(theme.get_color("base05"))
             };
 
-            ListItem::new(text).style(style)
+            let mut spans = match item {
+                SidebarItem::Server(guild) => {
+                    let icon = if icon_images && app.image_renderer.has_server_icon(&guild.id) {
+                        Span::raw(" ".repeat(SERVER_ICON_WIDTH as usize))
+                    } else {
+                        initials_icon(app, guild)
+                    };
+                    vec![Span::raw(text), icon, Span::raw(format!(" {}", guild.name))]
+                }
+                _ => vec![Span::raw(text)],
+            };
+            if unread.mentions > 0 {
+                spans.push(Span::raw(" "));
+                spans.push(mention_badge(app, unread.mentions));
+            }
+
+            ListItem::new(Line::from(spans)).style(style)
         })
         .collect();
 
@@ -100,12 +163,98 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
         Style::default().fg(theme.get_color("base03"))
     };
 
+    let connection_color = match app.connection {
+        ConnectionState::Ready => "base0B",
+        ConnectionState::Connecting | ConnectionState::Resuming => "base0A",
+        ConnectionState::Disconnected(_) | ConnectionState::Retrying { .. } => "base08",
+    };
+    let connection_status = Line::from(Span::styled(
+        format!(" ● {} ", app.connection.label()),
+        Style::default().fg(theme.get_color(connection_color)),
+    ));
+
     let list = List::new(list_items).block(
         Block::default()
             .borders(Borders::ALL)
-            .title("Navigation")
+            .title_bottom(connection_status)
+            .title(if app.config.notifications.do_not_disturb {
+                "Navigation (do not disturb)"
+            } else {
+                "Navigation"
+            })
             .border_style(border_style),
     );
 
     f.render_widget(list, area);
+
+    if icon_images {
+        for (visible_idx, item) in items.iter().enumerate() {
+            let SidebarItem::Server(guild) = item else {
+                continue;
+            };
+            if let Some(protocol) = app.image_renderer.get_server_icon(&guild.id) {
+                let icon_area = Rect {
+                    x: area.x + 1 + SERVER_ICON_OFFSET,
+                    y: area.y + 1 + visible_idx as u16,
+                    width: SERVER_ICON_WIDTH.min(area.width.saturating_sub(SERVER_ICON_OFFSET + 2)),
+                    height: 1,
+                };
+                f.render_stateful_widget(StatefulImage::default(), icon_area, protocol);
+            }
+        }
+    }
+}
+
+/// Two-cell initials badge, coloured per server, shown in place of the icon image
+fn initials_icon(app: &App, guild: &Guild) -> Span<'static> {
+    const BADGE_COLORS: [&str; 7] = ["base08", "base09", "base0A", "base0B", "base0C", "base0D", "base0E"];
+
+    let theme = app.theme();
+    let color = BADGE_COLORS[guild.id.bytes().map(|b| b as usize).sum::<usize>() % BADGE_COLORS.len()];
+
+    let mut badge = String::new();
+    let mut width = 0;
+    for c in guild.initials().chars() {
+        let char_width = c.width().unwrap_or(0);
+        if width + char_width > SERVER_ICON_WIDTH as usize {
+            break;
+        }
+        badge.push(c);
+        width += char_width;
+    }
+    badge.push_str(&" ".repeat(SERVER_ICON_WIDTH as usize - width));
+
+    Span::styled(
+        badge,
+        Style::default()
+            .fg(theme.get_color("base00"))
+            .bg(theme.get_color(color))
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+/// Red pill with the number of unread mentions
+fn mention_badge(app: &App, mentions: usize) -> Span<'static> {
+    let theme = app.theme();
+    let label = if mentions > 99 { "99+".to_string() } else { mentions.to_string() };
+
+    Span::styled(
+        format!(" {} ", label),
+        Style::default()
+            .fg(theme.get_color("base00"))
+            .bg(theme.get_color("base08"))
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+/// Whether a thread's parent channel sits inside a category
+fn is_categorized_thread(app: &App, thread: &Channel) -> bool {
+    let Some(parent_id) = thread.parent_id.as_deref() else {
+        return false;
+    };
+
+    app.channel_cache
+        .values()
+        .filter_map(|list| list.find(parent_id))
+        .any(|parent| parent.parent_id.is_some())
 }
@@ -0,0 +1,69 @@
+use crate::app::App;
+use crate::ui::utils::centered_rect;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+    let Some(setup) = &app.token_setup else {
+        return;
+    };
+    let theme = app.theme();
+    let kb = &app.config.keybinds;
+
+    let popup = centered_rect(60, 50, area);
+    f.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Discord token for '{}'", setup.account))
+        .title_bottom(format!(" {}: check and save, {}: cancel ", kb.select.key, kb.cancel_input.key))
+        .border_style(Style::default().fg(theme.get_color("base0A")));
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Length(2),
+        ])
+        .split(inner);
+
+    let mut lines = Vec::new();
+    if let Some(status) = &setup.status {
+        lines.push(Line::from(Span::styled(status.clone(), Style::default().fg(theme.get_color("base08")))));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        "Paste your token below. It is checked with Discord, then saved in the system keyring.",
+        Style::default().fg(theme.get_color("base05")),
+    )));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "To find it, open Discord in a browser, then Developer Tools → Application → Local Storage → discord.com and copy the `token` value.",
+        Style::default().fg(theme.get_color("base04")),
+    )));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), chunks[0]);
+
+    let input = Paragraph::new(setup.masked_input(chunks[1].width.saturating_sub(2) as usize))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.get_color("base03"))),
+        )
+        .style(Style::default().fg(theme.get_color("base05")));
+    f.render_widget(input, chunks[1]);
+
+    let hint = if setup.validating {
+        Span::styled("Checking the token with Discord...", Style::default().fg(theme.get_color("base0A")))
+    } else {
+        Span::styled("Never share your token with anyone", Style::default().fg(theme.get_color("base03")).add_modifier(Modifier::ITALIC))
+    };
+    f.render_widget(Paragraph::new(hint), chunks[2]);
+}
@@ -1,4 +1,7 @@
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use unicode_width::UnicodeWidthChar;
 
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -19,3 +22,157 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+/// Modifiers of a keybind as a prefix for its key, like "Ctrl+"
+pub fn format_modifiers(modifiers: &[String]) -> String {
+    if modifiers.is_empty() {
+        String::new()
+    } else {
+        format!("{}+", modifiers.join("+"))
+    }
+}
+
+/// Formats a unix timestamp (ms) as a short "5m ago" or "in 5m" style string
+pub fn format_relative_time(timestamp_ms: i64) -> String {
+    let delta = (chrono::Utc::now().timestamp_millis() - timestamp_ms) / 1000;
+    let secs = delta.abs();
+
+    let amount = match secs {
+        0..=59 => return "just now".to_string(),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86_399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86_400),
+    };
+
+    if delta < 0 {
+        format!("in {}", amount)
+    } else {
+        format!("{} ago", amount)
+    }
+}
+
+/// Word-wraps styled spans to `width` columns. Every output line starts with
+/// `first_prefix` (first line) or `rest_prefix` (continuation lines).
+pub fn wrap_spans(
+    spans: Vec<Span<'static>>,
+    width: usize,
+    first_prefix: Vec<Span<'static>>,
+    rest_prefix: Vec<Span<'static>>,
+) -> Vec<Line<'static>> {
+    let chars: Vec<(char, Style)> = spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .collect();
+
+    let mut rows: Vec<Vec<(char, Style)>> = Vec::new();
+    let mut row: Vec<(char, Style)> = Vec::new();
+    let mut row_width = 0;
+
+    let available = |first_row: bool| {
+        let prefix = if first_row { &first_prefix } else { &rest_prefix };
+        width.saturating_sub(prefix.iter().map(|s| s.width()).sum()).max(1)
+    };
+
+    for (c, style) in chars {
+        let char_width = c.width().unwrap_or(0);
+        let mut wrapped = false;
+
+        // A carried word can still be too long for the next row, so keep breaking until it fits
+        while !row.is_empty() && row_width + char_width > available(rows.is_empty()) {
+            let room = available(rows.is_empty());
+            // Break at the space being typed, else at the last space, else mid-word
+            let carry = match row.iter().rposition(|(c, _)| *c == ' ') {
+                _ if c == ' ' && row_width <= room => Vec::new(),
+                Some(space) if space > 0 => {
+                    let carry = row.split_off(space + 1);
+                    row.pop();
+                    carry
+                }
+                _ => row.split_off(fitting_chars(&row, room).max(1)),
+            };
+            rows.push(std::mem::replace(&mut row, carry));
+            row_width = row.iter().map(|(c, _)| c.width().unwrap_or(0)).sum();
+            wrapped = true;
+        }
+
+        if wrapped && c == ' ' && row.is_empty() {
+            continue;
+        }
+
+        row.push((c, style));
+        row_width += char_width;
+    }
+    rows.push(row);
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut line_spans = if i == 0 { first_prefix.clone() } else { rest_prefix.clone() };
+            let mut text = String::new();
+            let mut current_style = None;
+
+            for (c, style) in row {
+                if current_style.is_some_and(|s| s != style) {
+                    line_spans.push(Span::styled(std::mem::take(&mut text), current_style.unwrap()));
+                }
+                current_style = Some(style);
+                text.push(c);
+            }
+            if let Some(style) = current_style {
+                line_spans.push(Span::styled(text, style));
+            }
+
+            Line::from(line_spans)
+        })
+        .collect()
+}
+
+// How many leading characters of `row` fit in `width` columns
+fn fitting_chars(row: &[(char, Style)], width: usize) -> usize {
+    let mut used = 0;
+    row.iter()
+        .take_while(|(c, _)| {
+            used += c.width().unwrap_or(0);
+            used <= width
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap(text: &str, width: usize, first_prefix: &str, rest_prefix: &str) -> Vec<String> {
+        let prefix = |p: &str| if p.is_empty() { Vec::new() } else { vec![Span::raw(p.to_string())] };
+        wrap_spans(vec![Span::raw(text.to_string())], width, prefix(first_prefix), prefix(rest_prefix))
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn breaks_at_spaces() {
+        assert_eq!(wrap("ab cd ef", 5, "", ""), ["ab cd", "ef"]);
+        assert_eq!(wrap("ab cd", 5, "", ""), ["ab cd"]);
+        assert_eq!(wrap("ab cdef", 4, "", ""), ["ab", "cdef"]);
+    }
+
+    #[test]
+    fn breaks_a_word_one_column_too_wide() {
+        assert_eq!(wrap("abcde", 4, "", ""), ["abcd", "e"]);
+        assert_eq!(wrap("a bcdef", 4, "", ""), ["a", "bcde", "f"]);
+    }
+
+    #[test]
+    fn carried_word_is_broken_again_for_a_narrower_row() {
+        // The first row has 8 columns, continuation rows only 4 after their prefix
+        let lines = wrap("a bcdefgh", 8, "", "    ");
+        assert_eq!(lines, ["a", "    bcde", "    fgh"]);
+        assert!(lines.iter().all(|line| line.chars().count() <= 8));
+    }
+
+    #[test]
+    fn wide_characters_count_double() {
+        assert_eq!(wrap("日本語", 4, "", ""), ["日本", "語"]);
+    }
+}